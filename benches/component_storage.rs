@@ -0,0 +1,124 @@
+//! Compares the Sparse and Dense layouts of GenerationalIndexArray.
+//! Run with `cargo bench --features benchmarks --bench component_storage`.
+//!
+//! The game is only a binary, so the storage modules are compiled in here by path.
+//! They only lean on each other and serde, which is what lets them stand alone.
+
+#[macro_use]
+extern crate serde;
+
+// Most of the storage's API goes unused by the benchmarks
+#[allow(dead_code)]
+#[path = "../src/entities/generational_index.rs"]
+mod generational_index;
+#[allow(dead_code)]
+#[path = "../src/entities/generational_index_array.rs"]
+mod generational_index_array;
+#[allow(dead_code)]
+#[path = "../src/entities/generational_index_value.rs"]
+mod generational_index_value;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use generational_index::*;
+use generational_index_array::*;
+use generational_index_value::*;
+
+const ENTITY_COUNT: usize = 10_000;
+const STORAGES: [StorageKind; 2] = [StorageKind::Sparse, StorageKind::Dense];
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Particle {
+    position: [f32; 2],
+    velocity: [f32; 2],
+    lifetime: f32,
+}
+
+impl GenerationalIndexValue for Particle {
+    fn is_active(&self) -> bool {
+        true
+    }
+}
+
+/// An array with a slot for ENTITY_COUNT entities, where one in every `spacing`
+/// of them has a Particle.
+fn populated(
+    storage: StorageKind,
+    spacing: usize,
+) -> (Vec<GenerationalIndex>, GenerationalIndexArray<Particle>) {
+    let mut allocator = GenerationalIndexAllocator::new();
+    let mut array = GenerationalIndexArray::with_storage(storage);
+    let mut entities = Vec::with_capacity(ENTITY_COUNT);
+
+    for i in 0..ENTITY_COUNT {
+        let entity = allocator.allocate();
+        array.expand_list();
+        if i % spacing == 0 {
+            array.set(&entity, Particle::default());
+        }
+        entities.push(entity);
+    }
+
+    (entities, array)
+}
+
+fn iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iter_mut");
+    for &spacing in &[1, 10, 100] {
+        for &storage in &STORAGES {
+            let (_, mut array) = populated(storage, spacing);
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", storage), format!("1 in {}", spacing)),
+                &spacing,
+                |b, _| {
+                    b.iter(|| {
+                        for particle in array.iter_mut() {
+                            particle.position[0] += particle.velocity[0];
+                            particle.position[1] += particle.velocity[1];
+                        }
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get");
+    for &storage in &STORAGES {
+        let (entities, array) = populated(storage, 10);
+        group.bench_function(format!("{:?}", storage), |b| {
+            b.iter(|| {
+                let mut total = 0.0;
+                for entity in entities.iter() {
+                    if let Some(particle) = array.get(entity) {
+                        total += particle.lifetime;
+                    }
+                }
+                black_box(total)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("set_and_unset");
+    for &storage in &STORAGES {
+        let (entities, mut array) = populated(storage, 10);
+        group.bench_function(format!("{:?}", storage), |b| {
+            b.iter(|| {
+                for entity in entities.iter().skip(1).step_by(10) {
+                    array.set(entity, Particle::default());
+                }
+                for entity in entities.iter().skip(1).step_by(10) {
+                    array.unset(entity);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, iterate, lookup, churn);
+criterion_main!(benches);
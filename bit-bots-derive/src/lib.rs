@@ -1,8 +1,15 @@
+// We write these the way the rest of the tree does, which clippy doesn't care for
+#![allow(clippy::bool_comparison, clippy::unnecessary_map_or)]
 extern crate proc_macro;
 use crate::proc_macro::TokenStream;
-use quote::quote;
-use syn::DeriveInput;
-use heck::SnekCase;
+use heck::{SnekCase, TitleCase};
+use quote::{format_ident, quote};
+use syn::{
+    braced, bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Data, DeriveInput, Fields, GenericArgument, Ident, Lit, Meta, NestedMeta, PathArguments, Token, Type,
+};
 
 #[proc_macro_derive(SerializableComponent)]
 pub fn serializable_component_derive(input: TokenStream) -> TokenStream {
@@ -10,7 +17,7 @@ pub fn serializable_component_derive(input: TokenStream) -> TokenStream {
 
     // get the name of the type we want to implement the trait for
     let name = &input.ident;
-    let string_name = format!("{}", name).to_snek_case();
+    let string_name = serialized_key(name).to_string();
 
     let expanded = quote! {
         impl crate::components::SerializableComponent for #name {
@@ -21,3 +28,511 @@ pub fn serializable_component_derive(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Implements `ComponentBounds`, saving into the `SerializedEntity` field named after the
+/// type in snake case. Any field holding a `SerializableEntityReference`, or marked with
+/// `#[entity_ref]`, is fixed up on commit and on post deserialization.
+///
+/// `#[component(on_added = "method")]` and `#[component(on_removed = "method")]`
+/// call `self.method(entity, commands)` for those hooks, and
+/// `#[component(storage = "dense")]` packs the component's ComponentList.
+///
+/// The inspector is made out of every field marked with `#[inspect]`, in order:
+/// - `#[inspect]` uses the field's `InspectableField` impl.
+/// - `#[inspect(variants)]` picks from the variants of an enum or an Option of one.
+/// - `#[inspect(button)]` is a button which sets a bool field when it's pressed.
+/// - `#[inspect(with = "method")]` calls `self.method(label, &ip)` instead.
+/// - `#[inspect(label = "Name")]` sets the label, which is otherwise the field name.
+/// - `#[inspect(on_change = "method")]` calls `self.method()` after the field is edited.
+/// - `#[inspect(when = "field")]` only shows the field while that bool field is true.
+#[proc_macro_derive(ComponentBounds, attributes(component, inspect, entity_ref))]
+pub fn component_bounds_derive(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match component_bounds(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+fn component_bounds(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let serialized_key = serialized_key(name);
+
+    let fields = match &input.data {
+        Data::Struct(data_struct) => match &data_struct.fields {
+            Fields::Named(named) => named.named.iter().collect(),
+            Fields::Unit => vec![],
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "ComponentBounds can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "ComponentBounds can only be derived for structs",
+            ))
+        }
+    };
+
+    // Hooks and Storage
+    let mut storage = None;
+    let mut on_added = None;
+    let mut on_removed = None;
+    for meta in nested_metas(&input.attrs, "component")? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("storage") => {
+                storage = Some(match &name_value.lit {
+                    Lit::Str(kind) if kind.value() == "sparse" => quote!(Sparse),
+                    Lit::Str(kind) if kind.value() == "dense" => quote!(Dense),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected \"sparse\" or \"dense\"")),
+                });
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                let method = match &name_value.lit {
+                    Lit::Str(method) => format_ident!("{}", method.value()),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected the name of a method")),
+                };
+
+                if name_value.path.is_ident("on_added") {
+                    on_added = Some(method);
+                } else if name_value.path.is_ident("on_removed") {
+                    on_removed = Some(method);
+                } else {
+                    return Err(syn::Error::new_spanned(name_value, "unknown component attribute"));
+                }
+            }
+            meta => return Err(syn::Error::new_spanned(meta, "unknown component attribute")),
+        }
+    }
+
+    let mut entity_references = vec![];
+    let mut field_inspectors = vec![];
+
+    for field in fields {
+        let field_name = field.ident.as_ref().unwrap();
+
+        let marked_entity_reference = field.attrs.iter().any(|attr| attr.path.is_ident("entity_ref"));
+        if marked_entity_reference || holds_entity_reference(&field.ty) {
+            entity_references.push(field_name);
+        }
+
+        if field.attrs.iter().any(|attr| attr.path.is_ident("inspect")) {
+            field_inspectors.push(field_inspector(field_name, &field.ty, &field.attrs)?);
+        }
+    }
+
+    let entity_inspector = if field_inspectors.is_empty() {
+        quote! {
+            fn entity_inspector(&mut self, _: crate::components::InspectorParameters<'_, '_>) {}
+        }
+    } else {
+        quote! {
+            fn entity_inspector(&mut self, ip: crate::components::InspectorParameters<'_, '_>) {
+                #(#field_inspectors)*
+            }
+        }
+    };
+
+    let commit_to_scene = if entity_references.is_empty() {
+        quote! {
+            fn commit_to_scene(
+                &self,
+                serialized_entity: &mut crate::SerializedEntity,
+                active: bool,
+                _: &crate::ComponentList<crate::SerializationMarker>,
+            ) {
+                serialized_entity.#serialized_key = Some(crate::SerializedComponent {
+                    inner: self.clone(),
+                    active,
+                });
+            }
+        }
+    } else {
+        quote! {
+            fn commit_to_scene(
+                &self,
+                serialized_entity: &mut crate::SerializedEntity,
+                active: bool,
+                serialization_markers: &crate::ComponentList<crate::SerializationMarker>,
+            ) {
+                let mut clone = self.clone();
+                #(
+                    crate::components::EntityReferences::entity_id_to_serialized_refs(
+                        &mut clone.#entity_references,
+                        serialization_markers,
+                    );
+                )*
+
+                serialized_entity.#serialized_key = Some(crate::SerializedComponent { inner: clone, active });
+            }
+
+            fn post_deserialization(
+                &mut self,
+                _: crate::Entity,
+                serialization_markers: &crate::ComponentList<crate::SerializationMarker>,
+            ) {
+                #(
+                    crate::components::EntityReferences::serialized_refs_to_entity_id(
+                        &mut self.#entity_references,
+                        serialization_markers,
+                    );
+                )*
+            }
+        }
+    };
+
+    let hooks = [
+        (format_ident!("on_added"), on_added),
+        (format_ident!("on_removed"), on_removed),
+    ]
+    .iter()
+    .filter_map(|(hook, method)| {
+        method.as_ref().map(|method| {
+            quote! {
+                fn #hook(&mut self, entity: crate::Entity, commands: &mut crate::CommandBuffer) {
+                    self.#method(entity, commands);
+                }
+            }
+        })
+    })
+    .collect::<Vec<_>>();
+
+    let storage = storage.map(|kind| {
+        quote! {
+            fn storage() -> crate::StorageKind {
+                crate::StorageKind::#kind
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl crate::components::ComponentBounds for #name {
+            #entity_inspector
+            #(#hooks)*
+            #storage
+
+            fn is_serialized(&self, serialized_entity: &crate::SerializedEntity, active: bool) -> bool {
+                serialized_entity
+                    .#serialized_key
+                    .as_ref()
+                    .map_or(false, |s| s.active == active && &s.inner == self)
+            }
+
+            #commit_to_scene
+
+            fn uncommit_to_scene(&self, serialized_entity: &mut crate::SerializedEntity) {
+                serialized_entity.#serialized_key = None;
+            }
+        }
+    })
+}
+
+/// The `SerializedEntity` field a component is saved in: its type name in snake case.
+fn serialized_key(name: &Ident) -> Ident {
+    format_ident!("{}", format!("{}", name).to_snek_case())
+}
+
+/// Hands the component registry to `callback`, keying each serialized component by its
+/// `serialized_key`, so the registry never has to spell the key out again. This takes
+/// `callback; serialized { field: Type [FLAG], .. } unserialized { .. }` and calls
+/// `callback! { serialized { field: Type => key [FLAG], .. } unserialized { .. } }`.
+#[proc_macro]
+pub fn registered_components(input: TokenStream) -> TokenStream {
+    let Registry {
+        callback,
+        serialized,
+        unserialized,
+    } = syn::parse_macro_input!(input as Registry);
+
+    let serialized = serialized.iter().map(|entry| {
+        let RegistryEntry {
+            field,
+            component,
+            flag,
+        } = entry;
+        let key = serialized_key(&component.segments.last().unwrap().ident);
+        let flag = flag.iter();
+        quote! { #field: #component => #key #( [#flag] )* }
+    });
+    let unserialized = unserialized.iter().map(|entry| {
+        let RegistryEntry {
+            field,
+            component,
+            flag,
+        } = entry;
+        let flag = flag.iter();
+        quote! { #field: #component #( [#flag] )* }
+    });
+
+    TokenStream::from(quote! {
+        #callback! {
+            serialized { #( #serialized, )* }
+            unserialized { #( #unserialized, )* }
+        }
+    })
+}
+
+struct Registry {
+    callback: Ident,
+    serialized: Vec<RegistryEntry>,
+    unserialized: Vec<RegistryEntry>,
+}
+
+/// `field: Type`, with an optional `[FLAG]`.
+struct RegistryEntry {
+    field: Ident,
+    component: syn::Path,
+    flag: Option<Ident>,
+}
+
+impl Parse for Registry {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let callback = input.parse()?;
+        input.parse::<Token![;]>()?;
+
+        let section = |name: &str| -> syn::Result<Vec<RegistryEntry>> {
+            let section_name: Ident = input.parse()?;
+            if section_name != name {
+                return Err(syn::Error::new_spanned(
+                    section_name,
+                    format!("expected `{}`", name),
+                ));
+            }
+
+            let entries;
+            braced!(entries in input);
+            let entries = Punctuated::<RegistryEntry, Token![,]>::parse_terminated(&entries)?;
+            Ok(entries.into_iter().collect())
+        };
+
+        Ok(Registry {
+            callback,
+            serialized: section("serialized")?,
+            unserialized: section("unserialized")?,
+        })
+    }
+}
+
+impl Parse for RegistryEntry {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let field = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let component = input.parse()?;
+
+        let flag = if input.peek(syn::token::Bracket) {
+            let flag;
+            bracketed!(flag in input);
+            Some(flag.parse()?)
+        } else {
+            None
+        };
+
+        Ok(RegistryEntry {
+            field,
+            component,
+            flag,
+        })
+    }
+}
+
+/// Every `key(...)` item in the attributes named `attribute_name`. A bare `#[key]` has none.
+fn nested_metas(attrs: &[syn::Attribute], attribute_name: &str) -> syn::Result<Vec<NestedMeta>> {
+    let mut nested_metas = vec![];
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(attribute_name)) {
+        match attr.parse_meta()? {
+            Meta::Path(_) => {}
+            Meta::List(list) => nested_metas.extend(list.nested),
+            meta => return Err(syn::Error::new_spanned(meta, "expected a list of options")),
+        }
+    }
+
+    Ok(nested_metas)
+}
+
+/// The inspector for one `#[inspect]` field.
+fn field_inspector(
+    field_name: &syn::Ident,
+    field_type: &Type,
+    attrs: &[syn::Attribute],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut label = format!("{}", field_name).to_title_case();
+    let mut variants = false;
+    let mut button = false;
+    let mut with = None;
+    let mut on_change = None;
+    let mut when = None;
+    for meta in nested_metas(attrs, "inspect")? {
+        match meta {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("variants") => variants = true,
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("button") => button = true,
+            NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                let value = match &name_value.lit {
+                    Lit::Str(value) => value.value(),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+                };
+
+                if name_value.path.is_ident("label") {
+                    label = value;
+                } else if name_value.path.is_ident("with") {
+                    with = Some(format_ident!("{}", value));
+                } else if name_value.path.is_ident("on_change") {
+                    on_change = Some(format_ident!("{}", value));
+                } else if name_value.path.is_ident("when") {
+                    when = Some(format_ident!("{}", value));
+                } else {
+                    return Err(syn::Error::new_spanned(name_value, "unknown inspect attribute"));
+                }
+            }
+            meta => return Err(syn::Error::new_spanned(meta, "unknown inspect attribute")),
+        }
+    }
+
+    let mut inspector = if let Some(method) = with {
+        quote! {
+            self.#method(#label, &ip);
+        }
+    } else if button {
+        quote! {
+            if ip.ui.button(&imgui::im_str!("{}##{}", #label, ip.uid), [0.0, 0.0]) {
+                self.#field_name = true;
+            }
+        }
+    } else if variants && is_option(field_type) {
+        quote! {
+            if let Some(new_value) = crate::imgui_system::typed_enum_selection_option_named(
+                ip.ui,
+                &self.#field_name,
+                #label,
+                ip.uid,
+            ) {
+                self.#field_name = new_value;
+            }
+        }
+    } else if variants {
+        quote! {
+            if let Some(new_value) = crate::imgui_system::typed_enum_selection(ip.ui, &self.#field_name, ip.uid) {
+                self.#field_name = new_value;
+            }
+        }
+    } else {
+        quote! {
+            crate::components::InspectableField::inspect_field(&mut self.#field_name, #label, &ip);
+        }
+    };
+
+    if let Some(method) = on_change {
+        inspector = quote! {
+            {
+                let old_value = self.#field_name.clone();
+                #inspector
+                if self.#field_name != old_value {
+                    self.#method();
+                }
+            }
+        };
+    }
+
+    if let Some(condition) = when {
+        inspector = quote! {
+            if self.#condition {
+                #inspector
+            }
+        };
+    }
+
+    Ok(inspector)
+}
+
+/// Whether a type is a `SerializableEntityReference`, or wraps one in its generic
+/// arguments like `Option<Vec<SerializableEntityReference>>` does. Aliases can't be
+/// seen through, so those fields need an `#[entity_ref]`.
+fn holds_entity_reference(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path.path.segments.last().map_or(false, |segment| {
+            if segment.ident == "SerializableEntityReference" {
+                return true;
+            }
+
+            match &segment.arguments {
+                PathArguments::AngleBracketed(arguments) => {
+                    arguments.args.iter().any(|argument| match argument {
+                        GenericArgument::Type(ty) => holds_entity_reference(ty),
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+        }),
+        Type::Group(group) => holds_entity_reference(&group.elem),
+        Type::Paren(paren) => holds_entity_reference(&paren.elem),
+        _ => false,
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holds(ty: &str) -> bool {
+        holds_entity_reference(&syn::parse_str(ty).unwrap())
+    }
+
+    #[test]
+    fn entity_references_are_found_through_paths_and_wrappers() {
+        assert!(holds("SerializableEntityReference"));
+        assert!(holds("crate::components::SerializableEntityReference"));
+        assert!(holds("Option<SerializableEntityReference>"));
+        assert!(holds("Option<Vec<component_utils::SerializableEntityReference>>"));
+
+        assert!(holds("SerializablePrefabReference") == false);
+        assert!(holds("Vec<SerializableEntityReferenceList>") == false);
+        assert!(holds("Option<Entity>") == false);
+        assert!(holds("[SerializableEntityReference; 2]") == false);
+    }
+
+    #[test]
+    fn registered_components_are_keyed_by_their_type_name() {
+        let registry: Registry = syn::parse_str(
+            "define_lists; serialized { names: Name [NAME], boxes: physics::BoundingBox, } \
+             unserialized { markers: SerializationMarker [SERIALIZATION] }",
+        )
+        .unwrap();
+
+        assert_eq!(registry.callback, "define_lists");
+        let keys: Vec<_> = registry
+            .serialized
+            .iter()
+            .map(|entry| serialized_key(&entry.component.segments.last().unwrap().ident).to_string())
+            .collect();
+        assert_eq!(keys, ["name", "bounding_box"]);
+        assert_eq!(registry.serialized[0].flag.as_ref().unwrap(), "NAME");
+        assert!(registry.serialized[1].flag.is_none());
+        assert_eq!(registry.unserialized[0].field, "markers");
+
+        assert!(syn::parse_str::<Registry>("define_lists; unserialized {}").is_err());
+    }
+
+    #[test]
+    fn options_are_found_by_their_last_segment() {
+        assert!(is_option(&syn::parse_str("Option<SpriteName>").unwrap()));
+        assert!(is_option(
+            &syn::parse_str("std::option::Option<SpriteName>").unwrap()
+        ));
+        assert!(is_option(&syn::parse_str("OptionalSprite").unwrap()) == false);
+    }
+}
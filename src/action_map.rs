@@ -1,38 +1,254 @@
 use super::{
     cardinals::{CardinalPrime, FacingHorizontal},
-    hardware_interfaces::KeyboardInput,
+    hardware_interfaces::Input,
+    Binding, InputAction, InputBindings,
 };
-use winit::event::VirtualKeyCode as VK;
 
-#[derive(Debug, Default)]
+const MOVEMENT_ACTIONS: [(InputAction, CardinalPrime); 4] = [
+    (InputAction::MoveLeft, CardinalPrime::Left),
+    (InputAction::MoveRight, CardinalPrime::Right),
+    (InputAction::MoveDown, CardinalPrime::Down),
+    (InputAction::MoveUp, CardinalPrime::Up),
+];
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ActionMap {
     pub redo: bool,
     pub move_direction: Option<CardinalPrime>,
     pub switch_active_player: Option<FacingHorizontal>,
 }
 
+/// Tracks the direction being held down between frames, so that holding
+/// a direction walks across the Grid.
+#[derive(Debug, Default)]
+pub struct MovementRepeat {
+    direction: Option<CardinalPrime>,
+    held_for: f32,
+    next_repeat: f32,
+}
+
+impl MovementRepeat {
+    fn start(&mut self, direction: CardinalPrime, repeat_delay: f32) {
+        self.direction = Some(direction);
+        self.held_for = 0.0;
+        self.next_repeat = repeat_delay;
+    }
+}
+
 impl ActionMap {
-    pub fn update(&mut self, kb: &KeyboardInput) {
+    /// Returns true if nothing was asked for. Updating the Ecs with an
+    /// empty ActionMap never changes the board.
+    pub fn is_empty(&self) -> bool {
+        *self == ActionMap::default()
+    }
+
+    pub fn update(
+        &mut self,
+        input: &Input,
+        bindings: &InputBindings,
+        movement_repeat: &mut MovementRepeat,
+        delta_time: f32,
+    ) {
         *self = ActionMap::default();
+        let is_pressed = |action| bindings.is_pressed(action, input);
 
-        for key in kb.pressed_keys.iter() {
-            self.move_direction = match key {
-                VK::Left | VK::A => Some(CardinalPrime::Left),
-                VK::Right | VK::D => Some(CardinalPrime::Right),
-                VK::Down | VK::S => Some(CardinalPrime::Down),
-                VK::Up | VK::W => Some(CardinalPrime::Up),
-                _ => None,
+        // Movement
+        let (pressed_direction, held_direction) = Self::resolve_movement(input, bindings);
+
+        if let Some(pressed_direction) = pressed_direction {
+            self.move_direction = Some(pressed_direction);
+            movement_repeat.start(pressed_direction, bindings.repeat_delay);
+        } else if let Some(held_direction) = held_direction {
+            if movement_repeat.direction == Some(held_direction) {
+                movement_repeat.held_for += delta_time;
+
+                if bindings.hold_to_repeat && movement_repeat.held_for >= movement_repeat.next_repeat {
+                    self.move_direction = Some(held_direction);
+                    movement_repeat.next_repeat += bindings.repeat_interval.max(std::f32::EPSILON);
+                }
+            } else {
+                // The newest key was let go, so we fall back to an older one, with a fresh delay.
+                movement_repeat.start(held_direction, bindings.repeat_delay);
             }
+        } else {
+            *movement_repeat = MovementRepeat::default();
         }
 
-        if kb.is_pressed(VK::Q) {
+        if is_pressed(InputAction::SwitchPlayerLeft) {
             self.switch_active_player = Some(FacingHorizontal::Left);
         }
 
-        if kb.is_pressed(VK::E) {
+        if is_pressed(InputAction::SwitchPlayerRight) {
             self.switch_active_player = Some(FacingHorizontal::Right);
         }
 
-        self.redo = kb.is_pressed(VK::Z);
+        self.redo = is_pressed(InputAction::Undo);
+    }
+
+    /// Returns the direction pressed this frame and the direction being held.
+    /// The most recent movement key wins, and anything which isn't a movement key
+    /// is ignored. We can't tell when a mouse or gamepad button went down relative to a key,
+    /// so keys win over them.
+    fn resolve_movement(
+        input: &Input,
+        bindings: &InputBindings,
+    ) -> (Option<CardinalPrime>, Option<CardinalPrime>) {
+        let kb = &input.kb_input;
+
+        let direction_of = |key| {
+            MOVEMENT_ACTIONS
+                .iter()
+                .find(|(action, _)| bindings.bindings_for(*action).contains(&Binding::Key(key)))
+                .map(|(_, direction)| *direction)
+        };
+
+        let other_direction = |check: &dyn Fn(&Binding) -> bool| {
+            MOVEMENT_ACTIONS
+                .iter()
+                .find(|(action, _)| {
+                    bindings
+                        .bindings_for(*action)
+                        .iter()
+                        .any(|binding| match binding {
+                            Binding::Key(_) => false,
+                            _ => check(binding),
+                        })
+                })
+                .map(|(_, direction)| *direction)
+        };
+
+        let pressed = kb
+            .pressed_keys
+            .iter()
+            .filter_map(|key| direction_of(*key))
+            .last()
+            .or_else(|| other_direction(&|binding| binding.is_pressed(input)));
+
+        let held = kb
+            .held_keys
+            .iter()
+            .filter_map(|key| direction_of(*key))
+            .last()
+            .or_else(|| other_direction(&|binding| binding.is_held(input)));
+
+        (pressed, held)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::event::VirtualKeyCode as VK;
+
+    const DELTA_TIME: f32 = 0.125;
+
+    struct Keyboard {
+        input: Input,
+        bindings: InputBindings,
+        action_map: ActionMap,
+        movement_repeat: MovementRepeat,
+    }
+
+    impl Keyboard {
+        fn new() -> Self {
+            Self {
+                input: Input::new(),
+                bindings: InputBindings {
+                    repeat_delay: 0.5,
+                    repeat_interval: 0.25,
+                    ..Default::default()
+                },
+                action_map: ActionMap::default(),
+                movement_repeat: MovementRepeat::default(),
+            }
+        }
+
+        /// Runs a frame in which `press` go down, in order, and `release` come up.
+        fn frame(&mut self, press: &[VK], release: &[VK]) -> &ActionMap {
+            self.input.clear_input();
+
+            let kb = &mut self.input.kb_input;
+            for key in press {
+                kb.pressed_keys.push(*key);
+                kb.held_keys.push(*key);
+            }
+            for key in release {
+                kb.held_keys.retain(|held_key| held_key != key);
+                kb.released_keys.push(*key);
+            }
+
+            self.action_map
+                .update(&self.input, &self.bindings, &mut self.movement_repeat, DELTA_TIME);
+            &self.action_map
+        }
+
+        /// The direction moved in on each of the next `frames` frames, with nothing new pressed.
+        fn hold(&mut self, frames: usize) -> Vec<Option<CardinalPrime>> {
+            (0..frames).map(|_| self.frame(&[], &[]).move_direction).collect()
+        }
+    }
+
+    #[test]
+    fn keys_which_dont_move_dont_hide_a_movement_key() {
+        let mut keyboard = Keyboard::new();
+
+        let action_map = keyboard.frame(&[VK::Right, VK::Q], &[]);
+        assert_eq!(action_map.move_direction, Some(CardinalPrime::Right));
+        assert_eq!(action_map.switch_active_player, Some(FacingHorizontal::Left));
+
+        // Q is still held, but Right keeps repeating
+        assert_eq!(
+            keyboard.hold(4),
+            vec![None, None, None, Some(CardinalPrime::Right)]
+        );
+    }
+
+    #[test]
+    fn the_newest_movement_key_wins_until_it_is_let_go() {
+        let mut keyboard = Keyboard::new();
+
+        keyboard.frame(&[VK::Left], &[]);
+        assert_eq!(
+            keyboard.frame(&[VK::Up], &[]).move_direction,
+            Some(CardinalPrime::Up)
+        );
+        assert_eq!(keyboard.hold(4), vec![None, None, None, Some(CardinalPrime::Up)]);
+
+        // Letting go of Up falls back to Left, which waits out a fresh delay
+        assert_eq!(keyboard.frame(&[], &[VK::Up]).move_direction, None);
+        assert_eq!(
+            keyboard.hold(4),
+            vec![None, None, None, Some(CardinalPrime::Left)]
+        );
+    }
+
+    #[test]
+    fn held_directions_repeat_after_the_delay_and_then_every_interval() {
+        let mut keyboard = Keyboard::new();
+
+        assert_eq!(
+            keyboard.frame(&[VK::Down], &[]).move_direction,
+            Some(CardinalPrime::Down)
+        );
+        assert_eq!(
+            keyboard.hold(8),
+            vec![
+                None,
+                None,
+                None,
+                Some(CardinalPrime::Down),
+                None,
+                Some(CardinalPrime::Down),
+                None,
+                Some(CardinalPrime::Down),
+            ]
+        );
+
+        keyboard.bindings.hold_to_repeat = false;
+        assert_eq!(keyboard.hold(4), vec![None; 4]);
+
+        keyboard.frame(&[], &[VK::Down]);
+        assert_eq!(keyboard.movement_repeat.direction, None);
     }
 }
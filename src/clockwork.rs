@@ -1,14 +1,19 @@
 use super::{
-    systems::grid_system::Grid, systems::*, Ecs, HardwareInterface, ImGui, ImGuiDrawCommands,
-    ResourcesDatabase, TimeKeeper,
+    safe_files, systems::grid_system::Grid, systems::*, CommandBuffer, Ecs, HardwareInterface, ImGui,
+    ImGuiDrawCommands, ResourcesDatabase, RuntimeParameters, Scheduler, SystemParameters, Tick, TimeKeeper,
+    SYSTEMS,
 };
 use anyhow::Error;
-use array2d::Array2D;
 
 pub struct Clockwork {
     pub ecs: Ecs,
     pub action_map: ActionMap,
+    pub movement_repeat: MovementRepeat,
     pub grid: grid_system::Grid,
+    pub move_history: history_system::MoveHistory,
+    pub level_state: level_system::LevelState,
+    pub replay_recorder: Option<replay_system::ReplayRecorder>,
+    pub scheduler: Scheduler,
     pub hardware_interfaces: HardwareInterface,
     pub resources: ResourcesDatabase,
     pub time_keeper: TimeKeeper,
@@ -18,6 +23,7 @@ impl Clockwork {
     pub fn new() -> Result<Self, Error> {
         // Create Hardware Interfaces and Resources Handler
         let mut resources = ResourcesDatabase::new();
+        safe_files::set_backup_count(resources.config.backup_count);
         let mut hardware_interfaces = HardwareInterface::new(&resources.config)?;
         resources.initialize(&mut hardware_interfaces.renderer)?;
 
@@ -28,8 +34,13 @@ impl Clockwork {
             hardware_interfaces,
             resources,
             action_map: ActionMap::default(),
+            movement_repeat: MovementRepeat::default(),
             time_keeper: TimeKeeper::new(),
             grid,
+            move_history: history_system::MoveHistory::new(),
+            level_state: level_system::LevelState::default(),
+            replay_recorder: None,
+            scheduler: Scheduler::new(SYSTEMS)?,
         })
     }
 
@@ -49,6 +60,7 @@ impl Clockwork {
             // GET INPUT PER FRAME
             input_system::poll_events(
                 &mut self.hardware_interfaces.input,
+                self.hardware_interfaces.gamepad_source.as_mut(),
                 &mut self.hardware_interfaces.window.events_loop,
                 &self.hardware_interfaces.window.window,
                 &mut imgui,
@@ -73,6 +85,7 @@ impl Clockwork {
                 &mut self.hardware_interfaces,
                 &mut ui_handler,
                 &self.time_keeper,
+                &mut self.scheduler,
             );
 
             if scene_mode == SceneMode::Draft {
@@ -87,18 +100,27 @@ impl Clockwork {
             }
 
             // Make the Action Map:
-            self.action_map.update(&self.hardware_interfaces.input.kb_input);
+            self.action_map.update(
+                &self.hardware_interfaces.input,
+                &self.resources.input_bindings,
+                &mut self.movement_repeat,
+                self.time_keeper.delta_time,
+            );
 
             // Update
             while self.time_keeper.accumulator >= self.time_keeper.delta_time {
+                self.run_systems(Tick::Fixed, scene_mode)?;
+
                 if scene_mode == SceneMode::Playing {
-                    self.ecs.update(&mut self.grid, &self.action_map)?;
-                    self.ecs
-                        .update_resources(&self.resources, self.time_keeper.delta_time);
+                    if let Some(replay_recorder) = &mut self.replay_recorder {
+                        replay_recorder.record(self.time_keeper.tick_count, &self.action_map);
+                    }
                 }
                 self.time_keeper.accumulator -= self.time_keeper.delta_time;
             }
 
+            self.run_systems(Tick::Frame, scene_mode)?;
+
             // RENDER
             self.pre_render()?;
             self.render(ui_handler)?;
@@ -109,9 +131,33 @@ impl Clockwork {
 
         imgui.save_meta_data()?;
 
+        if let Some(replay_recorder) = self.replay_recorder.take() {
+            replay_recorder.finish(scene_system::current_scene_name(), &self.grid)?;
+        }
+
         Ok(())
     }
 
+    fn run_systems(&mut self, tick: Tick, scene_mode: SceneMode) -> Result<(), Error> {
+        let mut system_parameters = SystemParameters {
+            ecs: &mut self.ecs,
+            grid: &mut self.grid,
+            move_history: &mut self.move_history,
+            level_state: &mut self.level_state,
+            actions: &self.action_map,
+            delta_time: self.time_keeper.delta_time,
+            tick_count: self.time_keeper.tick_count,
+            runtime: Some(RuntimeParameters {
+                resources: &mut self.resources,
+                sound_player: &mut self.hardware_interfaces.sound_player,
+            }),
+            tick_state: Default::default(),
+            commands: CommandBuffer::new(),
+        };
+
+        self.scheduler.run(tick, scene_mode, &mut system_parameters)
+    }
+
     pub fn pre_render(&mut self) -> Result<(), Error> {
         renderer_system::pre_draw(
             &mut self.ecs.component_database,
@@ -164,8 +210,11 @@ impl Clockwork {
 
         if should_change_scene {
             let (ecs, grid) = Clockwork::start_scene(&mut self.resources, &mut self.hardware_interfaces)?;
+            // The old Scene's `on_removed` hooks don't run -- everything they'd clean up goes with it
             self.ecs = ecs;
             self.grid = grid;
+            self.move_history.clear();
+            self.level_state = level_system::LevelState::default();
 
             // Clear up the ImGui
             imgui.meta_data.entity_list_information.clear();
@@ -190,12 +239,19 @@ impl Clockwork {
             }
         }
 
-        // Grid
-        let mut grid = Array2D::filled_with(None, 5, 10);
         scene_graph::clear_root();
 
         // Initialize the ECS
         let mut ecs = Ecs::new(&resources.prefabs())?;
+        if let Err(e) = ecs.singleton_database.level.inner().validate() {
+            error!("{}'s Level Goals are invalid: {}", scene_system::current_scene_name(), e);
+        }
+
+        // Grid
+        let mut grid = Grid::new(
+            ecs.singleton_database.grid.inner().clone(),
+            serialization_util::interaction_rules::load_interaction_rules()?,
+        );
         ecs.game_start(resources, hardware_interfaces, &mut grid)?;
 
         info!("..Scene Loaded!");
@@ -0,0 +1,434 @@
+use super::{
+    prefab_system, scene_graph, ComponentDatabase, Ecs, Entity, NonInspectableEntities, PrefabMap,
+    RegisteredComponent,
+};
+use uuid::Uuid;
+
+/// An Entity in a command. Entities made by earlier commands in the same
+/// CommandBuffer don't exist until it's applied, so they're Pending until then.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CommandEntity {
+    Existing(Entity),
+    Pending(usize),
+}
+
+impl From<Entity> for CommandEntity {
+    fn from(entity: Entity) -> Self {
+        CommandEntity::Existing(entity)
+    }
+}
+
+/// The Entities which a CommandBuffer has made so far while it's being applied.
+pub struct PendingEntities(Vec<Option<Entity>>);
+
+impl PendingEntities {
+    /// The Entity a CommandEntity turned into. This is None if it was never made,
+    /// such as when its Prefab doesn't exist.
+    pub fn get(&self, entity: CommandEntity) -> Option<Entity> {
+        match entity {
+            CommandEntity::Existing(entity) => Some(entity),
+            CommandEntity::Pending(index) => self.0.get(index).copied().flatten(),
+        }
+    }
+}
+
+type ComponentCommand = Box<dyn FnOnce(&mut ComponentDatabase, Entity, &mut CommandBuffer)>;
+type EditCommand = Box<dyn FnOnce(&mut ComponentDatabase, Entity, &PendingEntities)>;
+
+enum Command {
+    Create,
+    InstantiatePrefab(Uuid),
+    Destroy(CommandEntity),
+    Component(CommandEntity, ComponentCommand),
+    Edit(CommandEntity, EditCommand),
+}
+
+/// Changes to the Ecs which are held onto until `apply`. Systems use this to make
+/// and destroy Entities while they're iterating over ComponentLists, which they
+/// can't do through the Ecs directly. The Scheduler applies every system's
+/// commands once its Tick is over.
+///
+/// Commands are applied in the order they're pushed. Components' `on_added` and
+/// `on_removed` hooks run as they're applied, and anything the hooks push is
+/// applied straight after.
+#[derive(Default)]
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+    pending_count: usize,
+}
+
+impl CommandBuffer {
+    /// If hooks keep pushing commands that make hooks run, we stop after this many rounds.
+    const MAX_HOOK_DEPTH: usize = 16;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Makes a new Entity with no components.
+    #[allow(dead_code)]
+    pub fn create_entity(&mut self) -> CommandEntity {
+        self.commands.push(Command::Create);
+        self.next_pending()
+    }
+
+    /// Makes a new Entity out of a Prefab, along with the rest of the Prefab's members.
+    pub fn instantiate_prefab(&mut self, prefab_id: Uuid) -> CommandEntity {
+        self.commands.push(Command::InstantiatePrefab(prefab_id));
+        self.next_pending()
+    }
+
+    /// Destroys the Entity along with everything under it in the scene graph.
+    pub fn destroy_entity(&mut self, entity: impl Into<CommandEntity>) {
+        self.commands.push(Command::Destroy(entity.into()));
+    }
+
+    /// Adds a component, replacing the one the Entity already has, if it has one.
+    pub fn add_component<T: RegisteredComponent>(&mut self, entity: impl Into<CommandEntity>, component: T) {
+        self.commands.push(Command::Component(
+            entity.into(),
+            Box::new(move |component_database, entity, hook_commands| {
+                let list = T::list_mut(component_database);
+                if let Some(old_component) = list.get_mut(&entity) {
+                    old_component.inner_mut().on_removed(entity, hook_commands);
+                }
+
+                list.set_component(&entity, component);
+                list.get_mut(&entity)
+                    .unwrap()
+                    .inner_mut()
+                    .on_added(entity, hook_commands);
+            }),
+        ));
+    }
+
+    pub fn remove_component<T: RegisteredComponent>(&mut self, entity: impl Into<CommandEntity>) {
+        self.commands.push(Command::Component(
+            entity.into(),
+            Box::new(|component_database, entity, hook_commands| {
+                let list = T::list_mut(component_database);
+                if let Some(component) = list.get_mut(&entity) {
+                    component.inner_mut().on_removed(entity, hook_commands);
+                    list.unset(&entity);
+                }
+            }),
+        ));
+    }
+
+    /// Edits a component once the Entity exists. `f` is given the PendingEntities
+    /// so the component can keep hold of other Entities made by this CommandBuffer.
+    pub fn edit_component<T: RegisteredComponent>(
+        &mut self,
+        entity: impl Into<CommandEntity>,
+        f: impl FnOnce(&mut T, &PendingEntities) + 'static,
+    ) {
+        self.commands.push(Command::Edit(
+            entity.into(),
+            Box::new(|component_database, entity, pending_entities| {
+                if let Some(component) = T::list_mut(component_database).get_mut(&entity) {
+                    f(component.inner_mut(), pending_entities);
+                }
+            }),
+        ));
+    }
+
+    /// This is the sync point, where every command is applied to the Ecs in order.
+    pub fn apply(&mut self, ecs: &mut Ecs, prefabs: &PrefabMap) {
+        self.apply_at_depth(ecs, prefabs, 0);
+    }
+
+    fn apply_at_depth(&mut self, ecs: &mut Ecs, prefabs: &PrefabMap, depth: usize) {
+        let mut pending_entities = PendingEntities(Vec::with_capacity(self.pending_count));
+        let mut hook_commands = CommandBuffer::new();
+
+        for command in self.commands.drain(..) {
+            match command {
+                Command::Create => {
+                    pending_entities.0.push(Some(ecs.create_entity()));
+                }
+
+                Command::InstantiatePrefab(prefab_id) => {
+                    if prefabs.contains_key(&prefab_id) == false {
+                        error!(
+                            "We couldn't instantiate Prefab {} -- it doesn't exist!",
+                            prefab_id
+                        );
+                        pending_entities.0.push(None);
+                        continue;
+                    }
+
+                    // Every Entity the Prefab makes goes on the end of `entities`
+                    let first_new_entity = ecs.entities.len();
+                    let entity = prefab_system::instantiate_entity_from_prefab(ecs, prefab_id, prefabs);
+
+                    if ecs.entity_allocator.is_live(entity) {
+                        for new_entity in ecs.entities[first_new_entity..].iter() {
+                            run_added_hooks(&mut ecs.component_database, *new_entity, &mut hook_commands);
+                        }
+                        pending_entities.0.push(Some(entity));
+                    } else {
+                        pending_entities.0.push(None);
+                    }
+                }
+
+                Command::Destroy(entity) => {
+                    if let Some(entity) = live_entity(ecs, &pending_entities, entity) {
+                        destroy_entity_tree(ecs, entity, &mut hook_commands);
+                    }
+                }
+
+                Command::Component(entity, f) => {
+                    if let Some(entity) = live_entity(ecs, &pending_entities, entity) {
+                        f(&mut ecs.component_database, entity, &mut hook_commands);
+                    }
+                }
+
+                Command::Edit(entity, f) => {
+                    if let Some(entity) = live_entity(ecs, &pending_entities, entity) {
+                        f(&mut ecs.component_database, entity, &pending_entities);
+                    }
+                }
+            }
+        }
+        self.pending_count = 0;
+
+        if hook_commands.is_empty() == false {
+            if depth < Self::MAX_HOOK_DEPTH {
+                hook_commands.apply_at_depth(ecs, prefabs, depth + 1);
+            } else {
+                error!(
+                    "Component hooks were still making commands after {} rounds, so we dropped them.",
+                    Self::MAX_HOOK_DEPTH
+                );
+            }
+        }
+    }
+
+    fn next_pending(&mut self) -> CommandEntity {
+        self.pending_count += 1;
+        CommandEntity::Pending(self.pending_count - 1)
+    }
+}
+
+/// The Entity a command is for, if it's still around. Commands for Entities which
+/// were destroyed before they ran are dropped.
+fn live_entity(ecs: &Ecs, pending_entities: &PendingEntities, entity: CommandEntity) -> Option<Entity> {
+    pending_entities
+        .get(entity)
+        .filter(|entity| ecs.entity_allocator.is_live(*entity))
+}
+
+pub(super) fn run_added_hooks(
+    component_database: &mut ComponentDatabase,
+    entity: Entity,
+    hook_commands: &mut CommandBuffer,
+) {
+    component_database.foreach_component_list_mut(NonInspectableEntities::all(), |component_list| {
+        if let Some((component, _)) = component_list.get_mut(&entity) {
+            component.on_added(entity, hook_commands);
+        }
+    });
+}
+
+/// Takes the Entity out of the scene graph and destroys it and every Entity under
+/// it, parents first. Each one's `on_removed` hooks run just before it goes.
+fn destroy_entity_tree(ecs: &mut Ecs, entity: Entity, hook_commands: &mut CommandBuffer) {
+    let tree = scene_graph::entity_tree(entity, &ecs.component_database.graph_nodes);
+    scene_graph::remove_from_parent(entity, &mut ecs.component_database.graph_nodes);
+
+    for entity in tree {
+        // A child reference can outlive its Entity
+        if ecs.entity_allocator.is_live(entity) == false {
+            continue;
+        }
+
+        ecs.component_database
+            .foreach_component_list_mut(NonInspectableEntities::all(), |component_list| {
+                if let Some((component, _)) = component_list.get_mut(&entity) {
+                    component.on_removed(entity, hook_commands);
+                }
+            });
+
+        Ecs::remove_entity_raw(
+            &mut ecs.entity_allocator,
+            &mut ecs.entities,
+            &mut ecs.component_database,
+            &entity,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        ComponentList, ConversantNPC, EntityAllocator, GraphNode, Name, SerializableEntityReference,
+        SerializationMarker, SingletonDatabase,
+    };
+    use super::*;
+
+    fn empty_ecs() -> Ecs {
+        Ecs {
+            component_database: ComponentDatabase::default(),
+            singleton_database: SingletonDatabase::default(),
+            entities: vec![],
+            entity_allocator: EntityAllocator::new(),
+        }
+    }
+
+    fn set_children(ecs: &mut Ecs, parent: Entity, children: &[Entity]) {
+        let serialization_markers = ComponentList::<SerializationMarker>::new();
+        let children = children
+            .iter()
+            .map(|child| SerializableEntityReference::from_entity_id(Some(*child), &serialization_markers))
+            .collect();
+
+        ecs.component_database.graph_nodes.set_component(
+            &parent,
+            GraphNode {
+                children: Some(children),
+            },
+        );
+    }
+
+    #[test]
+    fn destroying_an_entity_destroys_everything_under_it() {
+        let mut ecs = empty_ecs();
+        let holder = ecs.create_entity();
+        let parent = ecs.create_entity();
+        let child = ecs.create_entity();
+        let grandchild = ecs.create_entity();
+        let sibling = ecs.create_entity();
+        let runtime_ui = ecs.create_entity();
+
+        set_children(&mut ecs, holder, &[parent, sibling]);
+        set_children(&mut ecs, parent, &[child]);
+        set_children(&mut ecs, child, &[grandchild]);
+        ecs.component_database.conversant_npcs.set_component(
+            &grandchild,
+            ConversantNPC {
+                runtime_ui: Some(runtime_ui),
+                ..Default::default()
+            },
+        );
+
+        let mut commands = CommandBuffer::new();
+        commands.destroy_entity(parent);
+        commands.apply(&mut ecs, &PrefabMap::new());
+
+        for destroyed in [parent, child, grandchild].iter() {
+            assert!(ecs.entity_allocator.is_live(*destroyed) == false);
+        }
+        // The grandchild's `on_removed` hook took its UI down with it
+        assert!(ecs.entity_allocator.is_live(runtime_ui) == false);
+        assert_eq!(ecs.entities, vec![holder, sibling]);
+
+        let holder_children: Vec<_> = ecs
+            .component_database
+            .graph_nodes
+            .get(&holder)
+            .unwrap()
+            .inner()
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|child| child.target)
+            .collect();
+        assert_eq!(holder_children, vec![Some(sibling)]);
+    }
+
+    #[test]
+    fn removing_an_entity_through_the_ecs_runs_its_hooks() {
+        let mut ecs = empty_ecs();
+        let npc = ecs.create_entity();
+        let runtime_ui = ecs.create_entity();
+        ecs.component_database.conversant_npcs.set_component(
+            &npc,
+            ConversantNPC {
+                runtime_ui: Some(runtime_ui),
+                ..Default::default()
+            },
+        );
+
+        let clone = ecs.clone_entity(&npc, &PrefabMap::new());
+        let clone_npc = ecs.component_database.conversant_npcs.get(&clone).unwrap();
+        assert_eq!(clone_npc.inner().runtime_ui, None);
+
+        assert!(ecs.remove_entity(&npc, &PrefabMap::new()));
+        assert!(ecs.entity_allocator.is_live(runtime_ui) == false);
+        assert!(ecs.remove_entity(&npc, &PrefabMap::new()) == false);
+    }
+
+    fn npc_with_ui(runtime_ui: Entity) -> ConversantNPC {
+        ConversantNPC {
+            runtime_ui: Some(runtime_ui),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn created_entities_can_be_used_by_later_commands() {
+        let mut ecs = empty_ecs();
+        let mut commands = CommandBuffer::new();
+        let entity = commands.create_entity();
+        commands.add_component(
+            entity,
+            Name {
+                name: "Created".to_string(),
+            },
+        );
+        commands.apply(&mut ecs, &PrefabMap::new());
+
+        assert_eq!(ecs.entities.len(), 1);
+        let created = ecs.entities[0];
+        assert!(ecs.entity_allocator.is_live(created));
+        assert_eq!(
+            ecs.component_database.names.get(&created).unwrap().inner().name,
+            "Created"
+        );
+    }
+
+    #[test]
+    fn adding_a_component_runs_its_hooks() {
+        let mut ecs = empty_ecs();
+        let npc = ecs.create_entity();
+        let old_ui = ecs.create_entity();
+        let copied_ui = ecs.create_entity();
+        ecs.component_database
+            .conversant_npcs
+            .set_component(&npc, npc_with_ui(old_ui));
+
+        // The replaced NPC's `on_removed` takes its UI down, and the new one's
+        // `on_added` forgets the UI it was copied with
+        let mut commands = CommandBuffer::new();
+        commands.add_component(npc, npc_with_ui(copied_ui));
+        commands.apply(&mut ecs, &PrefabMap::new());
+
+        assert!(ecs.entity_allocator.is_live(old_ui) == false);
+        assert!(ecs.entity_allocator.is_live(copied_ui));
+        let new_npc = ecs.component_database.conversant_npcs.get(&npc).unwrap();
+        assert_eq!(new_npc.inner().runtime_ui, None);
+    }
+
+    #[test]
+    fn removing_a_component_runs_its_hooks() {
+        let mut ecs = empty_ecs();
+        let npc = ecs.create_entity();
+        let runtime_ui = ecs.create_entity();
+        ecs.component_database
+            .conversant_npcs
+            .set_component(&npc, npc_with_ui(runtime_ui));
+
+        let mut commands = CommandBuffer::new();
+        commands.remove_component::<ConversantNPC>(npc);
+        commands.apply(&mut ecs, &PrefabMap::new());
+
+        assert!(ecs.component_database.conversant_npcs.get(&npc).is_none());
+        assert!(ecs.entity_allocator.is_live(runtime_ui) == false);
+        assert!(ecs.entity_allocator.is_live(npc));
+    }
+}
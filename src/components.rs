@@ -25,7 +25,7 @@ pub use {
     component::*,
     component_utils::{
         bounding_circle::BoundingCircle,
-        component_database::{ComponentDatabase, NonInspectableEntities},
+        component_database::{ComponentDatabase, NonInspectableEntities, RegisteredComponent},
         component_traits::*,
         draw_layer::*,
         imgui_component_utils, Approach, DrawCommand, EditingMode,
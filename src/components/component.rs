@@ -1,4 +1,4 @@
-use super::{ComponentBounds, Entity, GenerationalIndexValue};
+use super::{ComponentBounds, Entity, GenerationalIndexValue, StorageKind};
 
 #[derive(Debug)]
 pub struct Component<T: ComponentBounds + Clone> {
@@ -53,6 +53,10 @@ impl<T: ComponentBounds + Clone> GenerationalIndexValue for Component<T> {
     fn is_active(&self) -> bool {
         self.is_active
     }
+
+    fn storage() -> StorageKind {
+        T::storage()
+    }
 }
 
 use std::fmt::{self, Display};
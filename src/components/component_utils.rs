@@ -1,6 +1,7 @@
 use super::*;
 
 mod approach;
+mod component_registry;
 mod draw_commands;
 mod edit_mode;
 pub mod imgui_component_utils;
@@ -3,27 +3,145 @@ use anyhow::Error;
 use std::collections::HashMap;
 use uuid::Uuid;
 
-pub struct ComponentDatabase {
-    pub names: ComponentList<Name>,
-    pub prefab_markers: ComponentList<PrefabMarker>,
-    pub transforms: ComponentList<Transform>,
-    pub players: ComponentList<Player>,
-    pub grid_objects: ComponentList<GridObject>,
-    pub graph_nodes: ComponentList<GraphNode>,
-    pub velocities: ComponentList<Velocity>,
-    pub sprites: ComponentList<Sprite>,
-    pub sound_sources: ComponentList<SoundSource>,
-    pub bounding_boxes: ComponentList<physics_components::BoundingBox>,
-    pub draw_rectangles: ComponentList<DrawRectangle>,
-    // pub tilemaps: ComponentList<tilemap::Tilemap>,
-    pub text_sources: ComponentList<TextSource>,
-    pub follows: ComponentList<Follow>,
-    pub conversant_npcs: ComponentList<ConversantNPC>,
-    pub scene_switchers: ComponentList<SceneSwitcher>,
-    pub serialization_markers: ComponentList<SerializationMarker>,
-    size: usize,
+macro_rules! define_component_database {
+    (
+        serialized {
+            $( $field:ident: $component:ty => $key:ident $( [$flag:ident] )? ),* $(,)?
+        }
+        unserialized {
+            $( $unserialized_field:ident: $unserialized_component:ty $( [$unserialized_flag:ident] )? ),* $(,)?
+        }
+    ) => {
+        pub struct ComponentDatabase {
+            $( pub $field: ComponentList<$component>, )*
+            $( pub $unserialized_field: ComponentList<$unserialized_component>, )*
+            size: usize,
+        }
+
+        impl Default for ComponentDatabase {
+            fn default() -> ComponentDatabase {
+                ComponentDatabase {
+                    $( $field: Default::default(), )*
+                    $( $unserialized_field: Default::default(), )*
+                    size: 0,
+                }
+            }
+        }
+
+        impl ComponentDatabase {
+            /// This loops over every component, except the non-inspectable ones
+            /// which aren't in `non_inspectable_entities`.
+            pub fn foreach_component_list_mut(
+                &mut self,
+                non_inspectable_entities: NonInspectableEntities,
+                mut f: impl FnMut(&mut dyn ComponentListBounds),
+            ) {
+                $(
+                    if non_inspectable_entities.contains(
+                        NonInspectableEntities::empty() $( | NonInspectableEntities::$flag )?
+                    ) {
+                        f(&mut self.$field);
+                    }
+                )*
+                $(
+                    if non_inspectable_entities.contains(
+                        NonInspectableEntities::empty() $( | NonInspectableEntities::$unserialized_flag )?
+                    ) {
+                        f(&mut self.$unserialized_field);
+                    }
+                )*
+            }
+
+            /// This loops over every component, except the non-inspectable ones
+            /// which aren't in `non_inspectable_entities`.
+            pub fn foreach_component_list(
+                &self,
+                non_inspectable_entities: NonInspectableEntities,
+                mut f: impl FnMut(&dyn ComponentListBounds),
+            ) {
+                $(
+                    if non_inspectable_entities.contains(
+                        NonInspectableEntities::empty() $( | NonInspectableEntities::$flag )?
+                    ) {
+                        f(&self.$field);
+                    }
+                )*
+                $(
+                    if non_inspectable_entities.contains(
+                        NonInspectableEntities::empty() $( | NonInspectableEntities::$unserialized_flag )?
+                    ) {
+                        f(&self.$unserialized_field);
+                    }
+                )*
+            }
+
+            /// This actually does the business of unwrapping a serialized entity and putting it inside
+            /// the Ecs.
+            fn load_serialized_entity_into_database(
+                &mut self,
+                entity: &Entity,
+                serialized_entity: SerializedEntity,
+                marker_map: &mut AssociatedEntityMap,
+            ) -> PostDeserializationRequired {
+                let SerializedEntity {
+                    $( $key, )*
+                    id: _,
+                    marker,
+                } = serialized_entity;
+
+                $(
+                    if let Some(serialized_component) = $key {
+                        self.$field.set_component_with_active(
+                            &entity,
+                            serialized_component.inner,
+                            serialized_component.active,
+                        );
+                    }
+                )*
+
+                // Singleton Components
+                if let Some(singleton_marker) = marker {
+                    marker_map.insert(singleton_marker, *entity);
+                }
+
+                PostDeserializationRequired
+            }
+        }
+
+        $(
+            impl RegisteredComponent for $component {
+                fn list(component_database: &ComponentDatabase) -> &ComponentList<Self> {
+                    &component_database.$field
+                }
+
+                fn list_mut(component_database: &mut ComponentDatabase) -> &mut ComponentList<Self> {
+                    &mut component_database.$field
+                }
+            }
+        )*
+        $(
+            impl RegisteredComponent for $unserialized_component {
+                fn list(component_database: &ComponentDatabase) -> &ComponentList<Self> {
+                    &component_database.$unserialized_field
+                }
+
+                fn list_mut(component_database: &mut ComponentDatabase) -> &mut ComponentList<Self> {
+                    &mut component_database.$unserialized_field
+                }
+            }
+        )*
+    };
+}
+
+/// A component with a list in the ComponentDatabase, which lets generic code,
+/// like a CommandBuffer, find the list for a component type.
+pub trait RegisteredComponent: ComponentBounds + Default + Clone + typename::TypeName + 'static {
+    fn list(component_database: &ComponentDatabase) -> &ComponentList<Self>;
+    fn list_mut(component_database: &mut ComponentDatabase) -> &mut ComponentList<Self>;
 }
 
+crate::with_registered_components!(define_component_database);
+
 impl ComponentDatabase {
     pub fn new(
         entity_allocator: &mut EntityAllocator,
@@ -31,16 +149,14 @@ impl ComponentDatabase {
         marker_map: &mut AssociatedEntityMap,
         prefabs: &PrefabMap,
     ) -> Result<ComponentDatabase, Error> {
-        // Update the database...
-        if cfg!(debug_assertions) {
-            if update_serialization::UPDATE_COMPONENT_DATABASE {
-                update_serialization::update_component_database()?;
-            }
-        }
-
         let saved_entities: HashMap<Uuid, SerializedEntity> =
             serialization_util::entities::load_all_entities()?;
 
+        // We load in order of id, so that every load of a Scene hands out the same Entities.
+        // Replays rely on this to compare the Grid they end on.
+        let mut saved_entities: Vec<(Uuid, SerializedEntity)> = saved_entities.into_iter().collect();
+        saved_entities.sort_by(|(a, _), (b, _)| a.cmp(b));
+
         let mut component_database = ComponentDatabase::default();
 
         let mut post_deserialization_needed = None;
@@ -98,101 +214,6 @@ impl ComponentDatabase {
         }
     }
 
-    // @update_components
-    /// This loops over every component, including the non-inspectable ones.
-    pub fn foreach_component_list_mut(
-        &mut self,
-        non_inspectable_entities: NonInspectableEntities,
-        mut f: impl FnMut(&mut dyn ComponentListBounds),
-    ) {
-        if non_inspectable_entities.contains(NonInspectableEntities::NAME) {
-            f(&mut self.names);
-        }
-
-        self.foreach_component_list_inspectable_mut(&mut f);
-        if non_inspectable_entities.contains(NonInspectableEntities::GRAPH_NODE) {
-            f(&mut self.graph_nodes);
-        }
-
-        if non_inspectable_entities.contains(NonInspectableEntities::PREFAB) {
-            f(&mut self.prefab_markers);
-        }
-
-        if non_inspectable_entities.contains(NonInspectableEntities::SERIALIZATION) {
-            f(&mut self.serialization_markers);
-        }
-    }
-
-    /// This loops over every component except for the following:
-    /// - Name
-    /// - PrefabMarker
-    /// - SerializationMarker
-    /// - GraphNode
-    /// Use `foreach_component_list` to iterate over all.
-    fn foreach_component_list_inspectable_mut(&mut self, f: &mut impl FnMut(&mut dyn ComponentListBounds)) {
-        f(&mut self.transforms);
-        f(&mut self.grid_objects);
-        f(&mut self.players);
-        f(&mut self.velocities);
-        f(&mut self.sprites);
-        f(&mut self.sound_sources);
-        f(&mut self.bounding_boxes);
-        f(&mut self.draw_rectangles);
-        // f(&mut self.tilemaps);
-        f(&mut self.scene_switchers);
-        f(&mut self.text_sources);
-        f(&mut self.follows);
-        f(&mut self.conversant_npcs);
-    }
-
-    // @update_components
-    /// This loops over every component, including the non-inspectable ones.
-    pub fn foreach_component_list(
-        &self,
-        non_inspectable_entities: NonInspectableEntities,
-        mut f: impl FnMut(&dyn ComponentListBounds),
-    ) {
-        if non_inspectable_entities.contains(NonInspectableEntities::NAME) {
-            f(&self.names);
-        }
-
-        self.foreach_component_list_inspectable(&mut f);
-
-        if non_inspectable_entities.contains(NonInspectableEntities::GRAPH_NODE) {
-            f(&self.graph_nodes);
-        }
-        if non_inspectable_entities.contains(NonInspectableEntities::PREFAB) {
-            f(&self.prefab_markers);
-        }
-
-        if non_inspectable_entities.contains(NonInspectableEntities::SERIALIZATION) {
-            f(&self.serialization_markers);
-        }
-    }
-
-    /// This loops over every component except for the following:
-    /// - Name
-    /// - PrefabMarker
-    /// - SerializationMarker
-    /// - GraphNode
-    /// Use `foreach_component_list` to iterate over all.
-    fn foreach_component_list_inspectable(&self, f: &mut impl FnMut(&dyn ComponentListBounds)) {
-        f(&self.transforms);
-        f(&self.grid_objects);
-        f(&self.players);
-        f(&self.graph_nodes);
-        f(&self.velocities);
-        f(&self.sprites);
-        f(&self.sound_sources);
-        f(&self.bounding_boxes);
-        f(&self.draw_rectangles);
-        // f(&self.tilemaps);
-        f(&self.scene_switchers);
-        f(&self.text_sources);
-        f(&self.follows);
-        f(&self.conversant_npcs);
-    }
-
     /// We can load anything using this function. The key thing to note here,
     /// however, is that this adds a SerializationData marker to whatever is being
     /// loaded. Ie -- if you load something with this function, it is now serialized.
@@ -255,8 +276,20 @@ impl ComponentDatabase {
         marker_map: &mut AssociatedEntityMap,
     ) -> Option<PostDeserializationRequired> {
         if let Some(prefab) = prefabs.get(&prefab_id) {
-            // Load the Main
-            let root_entity: SerializedEntity = prefab.root_entity().clone();
+            // Load the Main, along with everything it inherits if it's a variant
+            let root_entity: SerializedEntity =
+                match prefab_system::resolve_prefab_member(prefabs, prefab.root_id(), prefab.root_id()) {
+                    Ok(root_entity) => root_entity,
+                    Err(e) => {
+                        error!(
+                            "We couldn't load Prefab {} into entity {}. {}",
+                            prefab_id,
+                            Name::get_name_quick(&self.names, entity_to_load_into),
+                            e
+                        );
+                        return None;
+                    }
+                };
             let root_entity_children: SerializedComponentWrapper<GraphNode> = root_entity.graph_node.clone();
 
             let post_marker =
@@ -270,8 +303,12 @@ impl ComponentDatabase {
                     for child in children.iter() {
                         let member_serialized_id = child.target_serialized_id().unwrap();
 
-                        match prefab.members.get(&member_serialized_id).cloned() {
-                            Some(serialized_entity) => {
+                        match prefab_system::resolve_prefab_member(
+                            prefabs,
+                            prefab.root_id(),
+                            member_serialized_id,
+                        ) {
+                            Ok(serialized_entity) => {
                                 let new_id = Ecs::create_entity_raw(self, entity_allocator, entities);
 
                                 post_marker.fold_in(self.load_serialized_entity_into_database(
@@ -286,10 +323,11 @@ impl ComponentDatabase {
                                 );
                             }
 
-                            None => {
-                                error!("Our Root ID for Prefab {} had a child {} but we couldn't find it in the prefab list! Are you sure it's there?",
-                                        Name::get_name_even_quicklier(prefab.root_entity().name.as_ref().map(|sc| sc.inner.name.as_str()), prefab.root_id()),
-                                        member_serialized_id
+                            Err(e) => {
+                                error!("Our Root ID for Prefab {} had a child {} but we couldn't find it in the prefab list! Are you sure it's there? {}",
+                                        prefab_system::prefab_name(prefabs, prefab.root_id()),
+                                        member_serialized_id,
+                                        e
                                     );
                             }
                         }
@@ -304,7 +342,7 @@ impl ComponentDatabase {
                         {
                             error!(
                                 "Not all members of Prefab {prefab_name} were assigned into the Scene! Prefab {prefab_name} does not make a true Scene Graph!",
-                                prefab_name = Name::get_name_even_quicklier(prefab.root_entity().name.as_ref().map(|sc| sc.inner.name.as_str()), prefab.root_id()),
+                                prefab_name = prefab_system::prefab_name(prefabs, prefab.root_id()),
                             )
                         }
                     }
@@ -342,92 +380,6 @@ impl ComponentDatabase {
         self.load_serialized_entity_into_database(entity, serialized_entity, associated_entities)
     }
 
-    /// This actually does the business of unwrapping a serialized entity and putting it inside
-    /// the Ecs.
-    fn load_serialized_entity_into_database(
-        &mut self,
-        entity: &Entity,
-        serialized_entity: SerializedEntity,
-        marker_map: &mut AssociatedEntityMap,
-    ) -> PostDeserializationRequired {
-        let SerializedEntity {
-            bounding_box,
-            conversant_npc,
-            draw_rectangle,
-            follow,
-            id: _id,
-            marker: _marker, // we handle this in `load_serialized_entity`
-            name,
-            scene_switcher,
-            prefab_marker,
-            sound_source,
-            sprite,
-            text_source,
-            // tilemap,
-            transform,
-            velocity,
-            graph_node,
-            player,
-            grid_object,
-        } = serialized_entity;
-
-        // Helper macro
-        macro_rules! transfer_serialized_components {
-            ($component_name: ident, $component_database_name: ident) => {
-                if let Some(serialized_component) = $component_name {
-                    self.$component_database_name.set_component_with_active(
-                        &entity,
-                        serialized_component.inner,
-                        serialized_component.active,
-                    );
-                }
-            };
-        }
-
-        // @update_components
-        transfer_serialized_components!(prefab_marker, prefab_markers);
-        transfer_serialized_components!(name, names);
-        transfer_serialized_components!(transform, transforms);
-        transfer_serialized_components!(grid_object, grid_objects);
-        transfer_serialized_components!(scene_switcher, scene_switchers);
-        transfer_serialized_components!(player, players);
-        transfer_serialized_components!(graph_node, graph_nodes);
-        transfer_serialized_components!(sound_source, sound_sources);
-        transfer_serialized_components!(bounding_box, bounding_boxes);
-        transfer_serialized_components!(draw_rectangle, draw_rectangles);
-        transfer_serialized_components!(text_source, text_sources);
-        transfer_serialized_components!(velocity, velocities);
-        transfer_serialized_components!(sprite, sprites);
-        transfer_serialized_components!(follow, follows);
-        transfer_serialized_components!(conversant_npc, conversant_npcs);
-
-        // Tilemap Handling
-        // if let Some(serialized_component) = tilemap {
-        //     let tiles: Vec<Option<Tile>> =
-        //         serialization_util::tilemaps::load_tiles(&serialized_component.inner.tiles)
-        //             .map_err(|e| {
-        //                 error!(
-        //                     "Couldn't retrieve tilemaps for {}. Error: {}",
-        //                     &serialized_component.inner.tiles.relative_path, e
-        //                 )
-        //             })
-        //             .ok()
-        //             .unwrap_or_default();
-
-        //     let tilemap: tilemap::Tilemap = serialized_component.inner.to_tilemap(tiles);
-
-        //     self.tilemaps
-        //         .set_component_with_active(entity, tilemap, serialized_component.active);
-        // }
-
-        // Singleton Components
-        if let Some(singleton_marker) = serialized_entity.marker {
-            marker_map.insert(singleton_marker, *entity);
-        }
-
-        PostDeserializationRequired
-    }
-
     pub fn post_deserialization(
         &mut self,
         _: PostDeserializationRequired,
@@ -445,31 +397,6 @@ impl ComponentDatabase {
     }
 }
 
-impl Default for ComponentDatabase {
-    fn default() -> ComponentDatabase {
-        ComponentDatabase {
-            names: Default::default(),
-            prefab_markers: Default::default(),
-            transforms: Default::default(),
-            players: Default::default(),
-            grid_objects: Default::default(),
-            graph_nodes: Default::default(),
-            velocities: Default::default(),
-            sprites: Default::default(),
-            sound_sources: Default::default(),
-            bounding_boxes: Default::default(),
-            draw_rectangles: Default::default(),
-            // tilemaps: Default::default(),
-            text_sources: Default::default(),
-            follows: Default::default(),
-            conversant_npcs: Default::default(),
-            scene_switchers: Default::default(),
-            serialization_markers: Default::default(),
-            size: 0,
-        }
-    }
-}
-
 use bitflags::bitflags;
 bitflags! {
     pub struct NonInspectableEntities: u32 {
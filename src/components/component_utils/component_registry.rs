@@ -0,0 +1,44 @@
+/// Every component type, declared once. `ComponentDatabase` and `SerializedEntity`
+/// generate their fields, iteration and loading from this list, so adding a
+/// component is a line here (plus its `mod` in `components.rs`). The derives in
+/// `bit-bots-derive` write everything which belongs to one component; this is only for
+/// what's shared between all of them. A derive only ever sees the one type it's on, so it
+/// can't add that type's list to `ComponentDatabase` or its key to `SerializedEntity` --
+/// something has to list them all.
+///
+/// Each entry is `storage_field: Type`. A serialized component is saved under the
+/// same key `#[derive(ComponentBounds)]` uses, the type name in snake case, which
+/// `registered_components!` works out for us. An entry ending in `[FLAG]` is only
+/// visited when that `NonInspectableEntities` flag is asked for.
+///
+/// This is also the order the ComponentDatabase visits its lists in, and the order
+/// components are saved in. Add new components before `prefab_markers`, and don't
+/// reorder existing ones, or every saved Scene will change on its next save.
+#[macro_export]
+macro_rules! with_registered_components {
+    ($callback:ident) => {
+        ::bit_bots_derive::registered_components! {
+            $callback;
+            serialized {
+                names: Name [NAME],
+                players: Player,
+                transforms: Transform,
+                grid_objects: GridObject,
+                scene_switchers: SceneSwitcher,
+                graph_nodes: GraphNode [GRAPH_NODE],
+                velocities: Velocity,
+                sprites: Sprite,
+                sound_sources: SoundSource,
+                draw_rectangles: DrawRectangle,
+                bounding_boxes: physics_components::BoundingBox,
+                text_sources: TextSource,
+                follows: Follow,
+                conversant_npcs: ConversantNPC,
+                prefab_markers: PrefabMarker [PREFAB],
+            }
+            unserialized {
+                serialization_markers: SerializationMarker [SERIALIZATION],
+            }
+        }
+    };
+}
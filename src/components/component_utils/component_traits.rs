@@ -1,6 +1,8 @@
 use super::{
-    imgui_component_utils::*, Component, ComponentList, Entity, Name, PrefabMap, SerializationMarker,
-    SerializedEntity,
+    imgui_component_utils::*, imgui_system, Approach, Color, CommandBuffer, Component, ComponentList,
+    DrawOrder, Entity, Name, PrefabMap, Rect, RegisteredComponent, SerializableEntityReference,
+    SerializablePrefabReference, SerializationMarker, SerializedEntity, SoundName, SpriteRunningData,
+    StorageKind, Vec2, Vec2Int,
 };
 use imgui::Ui;
 
@@ -15,6 +17,20 @@ pub trait ComponentBounds {
     );
     fn uncommit_to_scene(&self, serialized_entity: &mut SerializedEntity);
     fn post_deserialization(&mut self, _: Entity, _: &ComponentList<SerializationMarker>) {}
+
+    /// Called when an Entity with this component is made out of a Prefab or cloned.
+    fn on_added(&mut self, _: Entity, _: &mut CommandBuffer) {}
+    /// Called when this component's Entity is destroyed. This doesn't happen when
+    /// the Scene is unloaded, since the whole Ecs is dropped with everything in it.
+    fn on_removed(&mut self, _: Entity, _: &mut CommandBuffer) {}
+
+    /// How this component's ComponentList lays out its components.
+    fn storage() -> StorageKind
+    where
+        Self: Sized,
+    {
+        StorageKind::Sparse
+    }
 }
 
 pub trait SerializableComponent:
@@ -38,6 +54,173 @@ pub struct InspectorParameters<'a, 'b> {
     pub is_open: bool,
 }
 
+/// A field which `#[derive(ComponentBounds)]` can put in an inspector with `#[inspect]`.
+pub trait InspectableField {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>);
+}
+
+impl InspectableField for bool {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        ip.ui.checkbox(&imgui::im_str!("{}##{}", label, ip.uid), self);
+    }
+}
+
+impl InspectableField for f32 {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        ip.ui
+            .drag_float(&imgui::im_str!("{}##{}", label, ip.uid), self)
+            .build();
+    }
+}
+
+impl InspectableField for String {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        let mut im_string = imgui::ImString::new(self.as_str());
+
+        if ip
+            .ui
+            .input_text(&imgui::im_str!("{}##{}", label, ip.uid), &mut im_string)
+            .resize_buffer(true)
+            .build()
+        {
+            *self = im_string.to_string();
+        }
+    }
+}
+
+impl InspectableField for Vec2 {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        self.inspector(ip.ui, &imgui::im_str!("{}##{}", label, ip.uid));
+    }
+}
+
+impl InspectableField for Color {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        self.inspect(ip.ui, label, ip.uid);
+    }
+}
+
+impl InspectableField for SerializableEntityReference {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        self.inspect(label, ip);
+    }
+}
+
+impl InspectableField for SerializablePrefabReference {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        self.inspect(label, ip);
+    }
+}
+
+impl InspectableField for Vec2Int {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        self.vec2int_inspector(ip.ui, &imgui::im_str!("{}##{}", label, ip.uid));
+    }
+}
+
+// Rects, DrawOrders and SpriteRunningData have a few parts, which all come with their own labels
+impl InspectableField for Rect {
+    fn inspect_field(&mut self, _: &str, ip: &InspectorParameters<'_, '_>) {
+        self.rect_inspector(ip.ui, ip.uid);
+    }
+}
+
+impl InspectableField for DrawOrder {
+    fn inspect_field(&mut self, _: &str, ip: &InspectorParameters<'_, '_>) {
+        self.inspect(ip.ui, ip.uid);
+    }
+}
+
+impl InspectableField for SpriteRunningData {
+    fn inspect_field(&mut self, _: &str, ip: &InspectorParameters<'_, '_>) {
+        self.inspect(ip.ui, ip.uid);
+
+        let mut frame = self.current_frame as i32;
+        if ip
+            .ui
+            .input_int(&imgui::im_str!("Current Frame##{}", ip.uid), &mut frame)
+            .build()
+        {
+            self.current_frame = frame.max(0) as usize;
+        }
+    }
+}
+
+impl InspectableField for Approach {
+    fn inspect_field(&mut self, _: &str, ip: &InspectorParameters<'_, '_>) {
+        if let Some(new_approach) = imgui_system::typed_enum_selection(ip.ui, self, ip.uid) {
+            *self = new_approach;
+        }
+
+        match self {
+            Approach::Instant => {}
+            Approach::Linear(speed) => {
+                ip.ui
+                    .drag_float(&imgui::im_str!("Speed##{}", ip.uid), speed)
+                    .build();
+            }
+            Approach::Asymptotic(weight) => {
+                ip.ui
+                    .drag_float(&imgui::im_str!("Weight##{}", ip.uid), weight)
+                    .build();
+            }
+        }
+    }
+}
+
+impl InspectableField for Option<SoundName> {
+    fn inspect_field(&mut self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        if let Some(new_sound_name) = imgui_system::sound_name_input(ip.ui, self, label, ip.uid) {
+            *self = new_sound_name;
+        }
+    }
+}
+
+/// Anything holding SerializableEntityReferences, which need to be switched between
+/// Entities and serialized IDs when they're saved and loaded.
+pub trait EntityReferences {
+    fn entity_id_to_serialized_refs(&mut self, serialization_markers: &ComponentList<SerializationMarker>);
+    fn serialized_refs_to_entity_id(&mut self, serialization_markers: &ComponentList<SerializationMarker>);
+}
+
+impl EntityReferences for SerializableEntityReference {
+    fn entity_id_to_serialized_refs(&mut self, serialization_markers: &ComponentList<SerializationMarker>) {
+        SerializableEntityReference::entity_id_to_serialized_refs(self, serialization_markers);
+    }
+
+    fn serialized_refs_to_entity_id(&mut self, serialization_markers: &ComponentList<SerializationMarker>) {
+        SerializableEntityReference::serialized_refs_to_entity_id(self, serialization_markers);
+    }
+}
+
+impl<T: EntityReferences> EntityReferences for Option<T> {
+    fn entity_id_to_serialized_refs(&mut self, serialization_markers: &ComponentList<SerializationMarker>) {
+        if let Some(inner) = self {
+            inner.entity_id_to_serialized_refs(serialization_markers);
+        }
+    }
+
+    fn serialized_refs_to_entity_id(&mut self, serialization_markers: &ComponentList<SerializationMarker>) {
+        if let Some(inner) = self {
+            inner.serialized_refs_to_entity_id(serialization_markers);
+        }
+    }
+}
+
+impl<T: EntityReferences> EntityReferences for Vec<T> {
+    fn entity_id_to_serialized_refs(&mut self, serialization_markers: &ComponentList<SerializationMarker>) {
+        for inner in self.iter_mut() {
+            inner.entity_id_to_serialized_refs(serialization_markers);
+        }
+    }
+
+    fn serialized_refs_to_entity_id(&mut self, serialization_markers: &ComponentList<SerializationMarker>) {
+        for inner in self.iter_mut() {
+            inner.serialized_refs_to_entity_id(serialization_markers);
+        }
+    }
+}
+
 pub trait ComponentListBounds {
     fn expand_list(&mut self);
     fn unset(&mut self, index: &Entity) -> bool;
@@ -46,7 +229,8 @@ pub trait ComponentListBounds {
     fn clone_entity(&mut self, index: &Entity, new_entity: &Entity);
 
     // IMGUI
-    fn component_add_button(&mut self, index: &Entity, ui: &imgui::Ui<'_>);
+    // Components are added and deleted through `commands`, so their hooks run.
+    fn component_add_button(&mut self, index: &Entity, ui: &imgui::Ui<'_>, commands: &mut CommandBuffer);
     #[must_use]
     fn component_inspector(
         &mut self,
@@ -57,6 +241,7 @@ pub trait ComponentListBounds {
         prefab_hashmap: &PrefabMap,
         ui: &imgui::Ui<'_>,
         is_open: bool,
+        commands: &mut CommandBuffer,
     ) -> Option<ComponentSerializationCommandType>;
 
     #[must_use]
@@ -100,7 +285,7 @@ pub trait ComponentListBounds {
 
 impl<T> ComponentListBounds for ComponentList<T>
 where
-    T: ComponentBounds + SerializableComponent,
+    T: RegisteredComponent + SerializableComponent,
 {
     fn expand_list(&mut self) {
         self.expand_list();
@@ -125,12 +310,12 @@ where
             self.set_component(new_entity, new_component);
         }
     }
-    fn component_add_button(&mut self, index: &Entity, ui: &imgui::Ui<'_>) {
+    fn component_add_button(&mut self, index: &Entity, ui: &imgui::Ui<'_>, commands: &mut CommandBuffer) {
         if imgui::MenuItem::new(&imgui::ImString::new(super::imgui_system::typed_text_ui::<T>()))
             .enabled(self.get(index).is_none())
             .build(ui)
         {
-            self.set_component(index, T::default());
+            commands.add_component(*index, T::default());
         }
     }
 
@@ -143,14 +328,20 @@ where
         prefab_hashmap: &PrefabMap,
         ui: &Ui<'_>,
         is_open: bool,
+        commands: &mut CommandBuffer,
     ) -> Option<ComponentSerializationCommandType> {
         if let Some(comp) = self.get_mut(entity) {
-            let ParentSyncStatus { serialized, prefab } = parent_sync_status.unwrap();
+            let ParentSyncStatus {
+                serialized,
+                prefab,
+                prefab_origin,
+            } = parent_sync_status.unwrap();
 
             let (serialization_command, delete) = super::imgui_system::component_inspector_raw(
                 comp,
                 serialized,
                 prefab,
+                prefab_origin,
                 entities,
                 entity_names,
                 prefab_hashmap,
@@ -161,7 +352,7 @@ where
             );
 
             if delete {
-                self.unset(entity);
+                commands.remove_component::<T>(*entity);
             }
 
             serialization_command
@@ -132,6 +132,8 @@ impl SyncStatus {
 pub struct ParentSyncStatus {
     pub serialized: SyncStatus,
     pub prefab: SyncStatus,
+    /// The Prefab in the inheritance chain which the component comes from.
+    pub prefab_origin: Option<uuid::Uuid>,
 }
 
 impl ParentSyncStatus {
@@ -145,6 +147,7 @@ impl ParentSyncStatus {
         ParentSyncStatus {
             serialized: SyncStatus::new(comp, serialized_entity, should_have_serialized_entity),
             prefab: SyncStatus::new(comp, prefab_entity, should_have_prefab_entity),
+            prefab_origin: None,
         }
     }
 }
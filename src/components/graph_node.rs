@@ -1,9 +1,19 @@
 use super::{
-    component_utils::RawComponent, imgui_system, ComponentBounds, ComponentData, ComponentList, Entity,
-    InspectorParameters, SerializableEntityReference, SerializationMarker, Transform,
+    component_utils::RawComponent, imgui_system, ComponentData, ComponentList, Entity, InspectorParameters,
+    SerializableEntityReference, SerializationMarker, Transform,
 };
 
-#[derive(Debug, Clone,SerializableComponent, PartialEq, Default, Serialize, Deserialize, typename::TypeName)]
+#[derive(
+    Debug,
+    Clone,
+    SerializableComponent,
+    ComponentBounds,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    typename::TypeName,
+)]
 #[serde(default)]
 pub struct GraphNode {
     pub children: Option<Vec<SerializableEntityReference>>,
@@ -106,50 +116,3 @@ impl GraphNode {
         }
     }
 }
-
-impl ComponentBounds for GraphNode {
-    fn entity_inspector(&mut self, _ip: InspectorParameters<'_, '_>) {
-        unimplemented!();
-    }
-
-    fn is_serialized(&self, serialized_entity: &super::SerializedEntity, active: bool) -> bool {
-        serialized_entity
-            .graph_node
-            .as_ref()
-            .map_or(false, |s| s.active == active && &s.inner == self)
-    }
-
-    fn commit_to_scene(
-        &self,
-        se: &mut super::SerializedEntity,
-        active: bool,
-        serialization_markers: &super::ComponentList<super::SerializationMarker>,
-    ) {
-        se.graph_node = Some({
-            let mut clone: super::GraphNode = self.clone();
-            if let Some(children) = clone.children.as_mut() {
-                for child in children.iter_mut() {
-                    child.entity_id_to_serialized_refs(&serialization_markers);
-                }
-            }
-
-            super::SerializedComponent { inner: clone, active }
-        });
-    }
-
-    fn uncommit_to_scene(&self, se: &mut super::SerializedEntity) {
-        se.graph_node = None;
-    }
-
-    fn post_deserialization(
-        &mut self,
-        _: super::Entity,
-        serialization_markers: &super::ComponentList<super::SerializationMarker>,
-    ) {
-        if let Some(children) = &mut self.children {
-            for child in children.iter_mut() {
-                child.serialized_refs_to_entity_id(&serialization_markers);
-            }
-        }
-    }
-}
@@ -1,14 +1,29 @@
-use super::{ComponentBounds, InspectorParameters, Vec2Int};
+use super::{imgui_system, InspectableField, InspectorParameters, Vec2Int};
 
-#[derive(Debug, Clone,SerializableComponent, PartialEq, Default, Serialize, Deserialize, typename::TypeName)]
+#[derive(
+    Debug,
+    Clone,
+    SerializableComponent,
+    ComponentBounds,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    typename::TypeName,
+)]
+#[component(storage = "dense")]
 #[serde(default)]
 pub struct GridObject {
+    #[inspect]
     pub grid_type: GridType,
     #[serde(skip)]
+    #[inspect(label = "Point", on_change = "clamp_move_to_point_pos")]
     pub move_to_point_pos: Vec2Int,
     #[serde(skip)]
+    #[inspect(button, label = "Move to Point")]
     pub move_to_point: bool,
     #[serde(skip)]
+    #[inspect(button, label = "Register Position")]
     pub register: bool,
 }
 
@@ -18,63 +33,10 @@ impl GridObject {
     }
 }
 
-impl ComponentBounds for GridObject {
-    fn entity_inspector(&mut self, ip: InspectorParameters<'_, '_>) {
-        if let Some(new_grid_type) = super::imgui_system::typed_enum_selection(ip.ui, &self.grid_type, ip.uid)
-        {
-            self.grid_type = new_grid_type;
-        }
-
-        if self
-            .move_to_point_pos
-            .vec2int_inspector(ip.ui, &imgui::im_str!("##Move to Point{}", ip.uid))
-        {
-            if self.move_to_point_pos.x < 0 {
-                self.move_to_point_pos.x = 0;
-            }
-
-            if self.move_to_point_pos.y < 0 {
-                self.move_to_point_pos.y = 0;
-            }
-        }
-
-        ip.ui.same_line(0.0);
-        if ip
-            .ui
-            .button(&imgui::im_str!("Move to Point##{}", ip.uid), [0.0, 0.0])
-        {
-            self.move_to_point = true;
-        }
-
-        if ip
-            .ui
-            .button(&imgui::im_str!("Register Position##{}", ip.uid), [0.0, 0.0])
-        {
-            self.register = true;
-        }
-    }
-
-    fn is_serialized(&self, serialized_entity: &super::SerializedEntity, active: bool) -> bool {
-        serialized_entity
-            .grid_object
-            .as_ref()
-            .map_or(false, |s| s.active == active && &s.inner == self)
-    }
-
-    fn commit_to_scene(
-        &self,
-        se: &mut super::SerializedEntity,
-        active: bool,
-        _: &super::ComponentList<super::SerializationMarker>,
-    ) {
-        se.grid_object = Some(super::SerializedComponent {
-            inner: self.clone(),
-            active,
-        });
-    }
-
-    fn uncommit_to_scene(&self, se: &mut super::SerializedEntity) {
-        se.grid_object = None;
+impl GridObject {
+    fn clamp_move_to_point_pos(&mut self) {
+        self.move_to_point_pos.x = self.move_to_point_pos.x.max(0);
+        self.move_to_point_pos.y = self.move_to_point_pos.y.max(0);
     }
 }
 
@@ -83,6 +45,8 @@ impl ComponentBounds for GridObject {
     Debug,
     Clone,
     PartialEq,
+    Eq,
+    Hash,
     Serialize,
     Deserialize,
     strum_macros::EnumIter,
@@ -96,6 +60,28 @@ pub enum GridType {
     Blockable,
     NonInteractable,
     Flag,
+    /// A kind of object the engine knows nothing about. What it does is
+    /// entirely up to the Scene's InteractionRules.
+    Custom(u8),
+}
+
+impl InspectableField for GridType {
+    fn inspect_field(&mut self, _: &str, ip: &InspectorParameters<'_, '_>) {
+        if let Some(new_grid_type) = imgui_system::typed_enum_selection(ip.ui, self, ip.uid) {
+            *self = new_grid_type;
+        }
+
+        if let GridType::Custom(kind) = self {
+            let mut kind_val = *kind as i32;
+            if ip
+                .ui
+                .input_int(&imgui::im_str!("Custom Kind##{}", ip.uid), &mut kind_val)
+                .build()
+            {
+                *kind = kind_val.max(0).min(u8::MAX as i32) as u8;
+            }
+        }
+    }
 }
 
 impl Default for GridType {
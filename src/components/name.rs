@@ -1,12 +1,18 @@
-use super::{
-    imgui_component_utils::*, imgui_system, Color, ComponentBounds, ComponentList, Entity,
-    InspectorParameters,
-};
+use super::{imgui_component_utils::*, imgui_system, Color, ComponentList, Entity};
 use imgui::{im_str, MenuItem};
 use regex::Regex;
 
 #[derive(
-    Debug, Default, Clone, PartialEq, Serialize, Deserialize, SerializableComponent, Hash, typename::TypeName,
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    SerializableComponent,
+    ComponentBounds,
+    Hash,
+    typename::TypeName,
 )]
 #[serde(default)]
 pub struct Name {
@@ -290,33 +296,3 @@ impl Name {
         }
     }
 }
-
-impl ComponentBounds for Name {
-    // Don't use this -- use the imgui function in the above impl
-    fn entity_inspector(&mut self, _inspector_parameters: InspectorParameters<'_, '_>) {
-        unimplemented!();
-    }
-
-    fn is_serialized(&self, serialized_entity: &super::SerializedEntity, active: bool) -> bool {
-        serialized_entity
-            .name
-            .as_ref()
-            .map_or(false, |s| s.active == active && &s.inner == self)
-    }
-
-    fn commit_to_scene(
-        &self,
-        se: &mut super::SerializedEntity,
-        active: bool,
-        _: &super::ComponentList<super::SerializationMarker>,
-    ) {
-        se.name = Some(super::SerializedComponent {
-            inner: self.clone(),
-            active,
-        });
-    }
-
-    fn uncommit_to_scene(&self, se: &mut super::SerializedEntity) {
-        se.name = None;
-    }
-}
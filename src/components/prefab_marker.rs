@@ -1,9 +1,10 @@
-use super::{ComponentBounds, InspectorParameters};
+use super::{prefab_system, InspectorParameters};
 use uuid::Uuid;
 
 #[derive(
     Debug,
     SerializableComponent,
+    ComponentBounds,
     Default,
     Clone,
     Serialize,
@@ -14,6 +15,7 @@ use uuid::Uuid;
     Hash,
 )]
 pub struct PrefabMarker {
+    #[inspect(with = "prefab_chain_inspector", label = "Original Prefab")]
     main_id: Uuid,
     sub_id: Uuid,
 }
@@ -39,37 +41,27 @@ impl PrefabMarker {
     }
 }
 
-impl ComponentBounds for PrefabMarker {
-    fn entity_inspector(&mut self, ip: InspectorParameters<'_, '_>) {
-        if let Some(serialized_name) = &ip.prefabs.get(&self.main_id).unwrap().root_entity().name {
-            ip.ui
-                .text(imgui::im_str!("Original Prefab: {}", serialized_name.inner.name));
-        } else {
-            ip.ui.text(imgui::im_str!("Original Prefab: {}", self.main_id));
-        }
-    }
-
-    fn is_serialized(&self, serialized_entity: &super::SerializedEntity, active: bool) -> bool {
-        serialized_entity
-            .prefab_marker
-            .as_ref()
-            .map_or(false, |s| s.active == active && &s.inner == self)
-    }
-
-    fn commit_to_scene(
-        &self,
-        se: &mut super::SerializedEntity,
-        active: bool,
-        _: &super::ComponentList<super::SerializationMarker>,
-    ) {
-        se.prefab_marker = Some(super::SerializedComponent {
-            inner: self.clone(),
-            active,
-        });
-    }
+impl PrefabMarker {
+    /// Shows the Prefab this was made from, and every Prefab that one is a variant of.
+    fn prefab_chain_inspector(&self, label: &str, ip: &InspectorParameters<'_, '_>) {
+        match prefab_system::prefab_chain(ip.prefabs, self.main_id, self.sub_id) {
+            Ok(chain) => {
+                ip.ui.text(imgui::im_str!(
+                    "{}: {}",
+                    label,
+                    prefab_system::prefab_name(ip.prefabs, self.main_id)
+                ));
 
-    fn uncommit_to_scene(&self, se: &mut super::SerializedEntity) {
-        se.prefab_marker = None;
+                // Every Prefab under it, if it's a variant
+                for level in chain.iter().skip(1) {
+                    ip.ui.text(imgui::im_str!(
+                        "Based on: {}",
+                        prefab_system::prefab_name(ip.prefabs, level.prefab_id)
+                    ));
+                }
+            }
+            Err(e) => ip.ui.text(imgui::im_str!("Broken Prefab: {}", e)),
+        }
     }
 }
 
@@ -3,56 +3,91 @@ use super::*;
 #[derive(
     Debug,
     SerializableComponent,
+    ComponentBounds,
     Clone,
     Default,
     PartialEq,
-    Eq,
     Serialize,
     Deserialize,
     typename::TypeName,
-    Hash,
 )]
 #[serde(default)]
 pub struct SoundSource {
-    pub sound_to_play: Option<SoundResource>,
+    /// The name of a sound in `assets/audio`.
+    #[inspect(label = "Sound")]
+    pub sound_to_play: Option<SoundName>,
+    #[inspect]
     pub muted: bool,
+    /// Spatial sounds are panned and attenuated by where they are relative to the Camera.
+    #[inspect]
+    pub spatial: bool,
+    #[inspect(when = "spatial")]
+    pub falloff: SoundFalloff,
 }
 
-use imgui::*;
-impl ComponentBounds for SoundSource {
-    fn entity_inspector(&mut self, inspector_parameters: InspectorParameters<'_, '_>) {
-        let InspectorParameters { uid, ui, .. } = inspector_parameters;
+/// How a spatial sound fades and pans with its distance from the Camera.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundFalloff {
+    /// Closer than this, the sound plays at full volume.
+    pub min_distance: f32,
+    /// Further than this, the sound is silent.
+    pub max_distance: f32,
+    /// How far to the side the sound has to be to play from only one speaker.
+    pub pan_width: f32,
+}
 
-        if let Some(sound_to_play_maybe) =
-            imgui_system::typed_enum_selection_option(ui, &self.sound_to_play, uid)
-        {
-            self.sound_to_play = sound_to_play_maybe;
+impl Default for SoundFalloff {
+    fn default() -> Self {
+        Self {
+            min_distance: 64.0,
+            max_distance: 960.0,
+            pan_width: 640.0,
         }
-
-        // MUTED
-        ui.checkbox(&im_str!("Muted##{}", uid), &mut self.muted);
     }
+}
 
-    fn is_serialized(&self, serialized_entity: &super::SerializedEntity, active: bool) -> bool {
-        serialized_entity
-            .sound_source
-            .as_ref()
-            .map_or(false, |s| s.active == active && &s.inner == self)
-    }
+impl SoundFalloff {
+    /// The left and right volumes for a sound `offset` away from the listener.
+    pub fn channel_volumes(&self, offset: Vec2) -> [f32; 2] {
+        let distance = offset.magnitude();
+        let attenuation = if distance <= self.min_distance {
+            1.0
+        } else if distance >= self.max_distance {
+            0.0
+        } else {
+            1.0 - (distance - self.min_distance) / (self.max_distance - self.min_distance)
+        };
 
-    fn commit_to_scene(
-        &self,
-        se: &mut super::SerializedEntity,
-        active: bool,
-        _: &super::ComponentList<super::SerializationMarker>,
-    ) {
-        se.sound_source = Some(super::SerializedComponent {
-            inner: self.clone(),
-            active,
-        });
+        let pan = if self.pan_width > 0.0 {
+            (offset.x / self.pan_width).max(-1.0).min(1.0)
+        } else {
+            0.0
+        };
+
+        [
+            attenuation * (1.0 - pan).min(1.0),
+            attenuation * (1.0 + pan).min(1.0),
+        ]
     }
+}
+
+impl InspectableField for SoundFalloff {
+    fn inspect_field(&mut self, _: &str, ip: &InspectorParameters<'_, '_>) {
+        let InspectorParameters { ui, uid, .. } = ip;
+
+        ui.drag_float(&imgui::im_str!("Min Distance##{}", uid), &mut self.min_distance)
+            .min(0.0)
+            .build();
+        ui.drag_float(&imgui::im_str!("Max Distance##{}", uid), &mut self.max_distance)
+            .min(self.min_distance)
+            .build();
+        ui.drag_float(&imgui::im_str!("Pan Width##{}", uid), &mut self.pan_width)
+            .min(0.0)
+            .build();
 
-    fn uncommit_to_scene(&self, se: &mut super::SerializedEntity) {
-        se.sound_source = None;
+        self.min_distance = self.min_distance.max(0.0);
+        self.max_distance = self.max_distance.max(self.min_distance);
+        self.pan_width = self.pan_width.max(0.0);
     }
 }
@@ -1,13 +1,21 @@
-use super::{
-    component_utils::SpriteRunningData, imgui_system, sprite_resources::*, ComponentBounds,
-    InspectorParameters,
-};
+use super::{component_utils::SpriteRunningData, sprite_resources::*};
 
 #[derive(
-    Serialize, SerializableComponent, Deserialize, Debug, Clone, PartialEq, typename::TypeName, Default,
+    Serialize,
+    SerializableComponent,
+    ComponentBounds,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+    typename::TypeName,
+    Default,
 )]
+#[component(storage = "dense")]
 pub struct Sprite {
+    #[inspect(variants, label = "Sprite", on_change = "reset_animation")]
     pub sprite_name: Option<SpriteName>,
+    #[inspect]
     pub running_data: SpriteRunningData,
 }
 
@@ -41,48 +49,3 @@ impl Sprite {
     //     }
     // }
 }
-
-impl ComponentBounds for Sprite {
-    fn entity_inspector(&mut self, inspector_parameters: InspectorParameters<'_, '_>) {
-        let InspectorParameters { uid, ui, .. } = inspector_parameters;
-
-        if let Some(new_sprite) = imgui_system::typed_enum_selection_option(ui, &self.sprite_name, uid) {
-            self.reset_animation();
-            self.sprite_name = new_sprite;
-        };
-
-        self.running_data.inspect(ui, uid);
-
-        // CURRENT FRAME
-        let mut frame = self.running_data.current_frame as i32;
-        if ui
-            .input_int(&imgui::im_str!("Current Frame##{}", uid), &mut frame)
-            .build()
-        {
-            self.running_data.current_frame = frame as usize;
-        }
-    }
-
-    fn is_serialized(&self, serialized_entity: &super::SerializedEntity, active: bool) -> bool {
-        serialized_entity
-            .sprite
-            .as_ref()
-            .map_or(false, |s| s.active == active && &s.inner == self)
-    }
-
-    fn commit_to_scene(
-        &self,
-        se: &mut super::SerializedEntity,
-        active: bool,
-        _: &super::ComponentList<super::SerializationMarker>,
-    ) {
-        se.sprite = Some(super::SerializedComponent {
-            inner: self.clone(),
-            active,
-        });
-    }
-
-    fn uncommit_to_scene(&self, se: &mut super::SerializedEntity) {
-        se.sprite = None;
-    }
-}
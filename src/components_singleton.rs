@@ -1,13 +1,19 @@
 use super::*;
 
 mod camera;
+mod grid_info;
+mod level_goals;
 mod markers;
 mod rendering_utility;
+mod scene_music;
 mod singleton_component;
 mod singleton_database;
 
 pub use camera::{Camera, CameraMode};
+pub use grid_info::GridInfo;
+pub use level_goals::{GoalCondition, LevelGoals};
 pub use markers::Marker;
 pub use rendering_utility::{BasicTextures, RenderingUtility};
+pub use scene_music::SceneMusic;
 pub use singleton_component::{SingletonBounds, SingletonComponent};
 pub use singleton_database::{AssociatedEntityMap, SingletonDatabase};
@@ -1,4 +1,7 @@
-use super::{imgui_system, HardwareInterface, InspectorParameters, SingletonBounds, Vec2};
+use super::{
+    imgui_system, ComponentList, Entity, HardwareInterface, InspectorParameters, SingletonBounds, Transform,
+    Vec2,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Camera {
@@ -19,6 +22,18 @@ impl Camera {
         self.native_resolution / self.zoom_level
     }
 
+    /// Where the Camera is, which is its associated entity's Transform if it has one.
+    pub fn world_position(
+        &self,
+        camera_entity: Option<&Entity>,
+        transforms: &ComponentList<Transform>,
+    ) -> Vec2 {
+        camera_entity
+            .and_then(|camera_entity| transforms.get(camera_entity))
+            .map(|transform| transform.inner().world_position())
+            .unwrap_or(self.default_position)
+    }
+
     pub fn display_size(&self) -> Vec2 {
         self.display_size.unwrap()
     }
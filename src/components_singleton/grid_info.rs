@@ -0,0 +1,160 @@
+use super::{cardinals::CardinalPrime, imgui_system, InspectorParameters, SingletonBounds, Vec2};
+
+/// Describes the shape of a Scene's Grid. Each Scene declares its own
+/// in its singleton data, and the Grid is rebuilt from it on Scene load.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct GridInfo {
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: Vec2,
+    /// The world position of the center of cell (0, 0).
+    pub origin: Vec2,
+}
+
+impl GridInfo {
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn contains(&self, pos: (usize, usize)) -> bool {
+        pos.0 < self.width && pos.1 < self.height
+    }
+
+    pub fn contains_world_position(&self, pos: Vec2) -> bool {
+        self.world_to_grid_position(pos).is_some()
+    }
+
+    /// The cell which `pos` is in, or None if it's off the Grid. Each cell reaches
+    /// half a cell out from its center on every side.
+    pub fn world_to_grid_position(&self, pos: Vec2) -> Option<(usize, usize)> {
+        let corner = self.origin - self.cell_size / 2.0;
+        let cell = (pos - corner).cwise_div(self.cell_size).floor();
+
+        if cell.x < 0.0 || cell.y < 0.0 {
+            return None;
+        }
+
+        let cell = (cell.x as usize, cell.y as usize);
+        if self.contains(cell) {
+            Some(cell)
+        } else {
+            None
+        }
+    }
+
+    pub fn grid_to_world_position(&self, pos: (usize, usize)) -> Vec2 {
+        Vec2::new(pos.0 as f32, pos.1 as f32).cwise_product(self.cell_size) + self.origin
+    }
+
+    /// Returns the position one cell over in `direction`, or None if that
+    /// would take us off the Grid.
+    pub fn move_position(&self, pos: (usize, usize), direction: CardinalPrime) -> Option<(usize, usize)> {
+        match direction {
+            CardinalPrime::Right => {
+                if pos.0 + 1 < self.width {
+                    Some((pos.0 + 1, pos.1))
+                } else {
+                    None
+                }
+            }
+            CardinalPrime::Up => {
+                if pos.1 + 1 < self.height {
+                    Some((pos.0, pos.1 + 1))
+                } else {
+                    None
+                }
+            }
+            CardinalPrime::Left => {
+                if pos.0 != 0 {
+                    Some((pos.0 - 1, pos.1))
+                } else {
+                    None
+                }
+            }
+            CardinalPrime::Down => {
+                if pos.1 != 0 {
+                    Some((pos.0, pos.1 - 1))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+impl Default for GridInfo {
+    fn default() -> Self {
+        Self {
+            width: 5,
+            height: 10,
+            cell_size: Vec2::new(8.0, 8.0),
+            origin: Vec2::new(8.0, 8.0),
+        }
+    }
+}
+
+impl SingletonBounds for GridInfo {
+    fn entity_inspector(&mut self, inspector_parameters: InspectorParameters<'_, '_>) {
+        let InspectorParameters { uid, ui, .. } = inspector_parameters;
+
+        imgui_system::input_usize(ui, &imgui::im_str!("Width##{}", uid), &mut self.width);
+        imgui_system::input_usize(ui, &imgui::im_str!("Height##{}", uid), &mut self.height);
+
+        if self
+            .cell_size
+            .inspector(ui, &imgui::im_str!("Cell Size##{}", uid))
+        {
+            self.cell_size.x = f32::max(1.0, self.cell_size.x);
+            self.cell_size.y = f32::max(1.0, self.cell_size.y);
+        }
+
+        self.origin.inspector(ui, &imgui::im_str!("Origin##{}", uid));
+
+        imgui_system::help_marker(
+            ui,
+            "Changes to the Grid take effect when the Scene is next loaded.",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_positions_land_in_the_cell_whose_center_is_nearest() {
+        let grid_info = GridInfo::default();
+
+        let center = grid_info.grid_to_world_position((2, 3));
+        assert_eq!(grid_info.world_to_grid_position(center), Some((2, 3)));
+        assert_eq!(
+            grid_info.world_to_grid_position(center - Vec2::new(3.0, 3.0)),
+            Some((2, 3))
+        );
+        assert_eq!(
+            grid_info.world_to_grid_position(center + Vec2::new(3.0, 3.0)),
+            Some((2, 3))
+        );
+        assert_eq!(
+            grid_info.world_to_grid_position(center - Vec2::new(5.0, 0.0)),
+            Some((1, 3))
+        );
+    }
+
+    #[test]
+    fn world_positions_off_the_grid_have_no_cell() {
+        let grid_info = GridInfo::default();
+
+        assert_eq!(grid_info.world_to_grid_position(Vec2::new(3.0, 8.0)), None);
+        assert_eq!(grid_info.world_to_grid_position(Vec2::new(-20.0, -20.0)), None);
+        assert_eq!(
+            grid_info.world_to_grid_position(grid_info.grid_to_world_position((5, 0))),
+            None
+        );
+        assert_eq!(
+            grid_info.world_to_grid_position(grid_info.grid_to_world_position((4, 9))),
+            Some((4, 9))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use super::{imgui_system, GridType, InspectorParameters, SingletonBounds};
+use anyhow::Error;
+
+/// A single thing which must be true for a Scene to be complete.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum GoalCondition {
+    /// Every GridObject of type `mover` shares a cell with a GridObject
+    /// of type `target`. If there are no movers on the Grid, this is never met.
+    AllOn { mover: GridType, target: GridType },
+    /// A GridEvent by this name was raised this Turn.
+    EventRaised(String),
+}
+
+/// The completion conditions of a Scene. The Scene is complete once every
+/// condition is met, and failed if `turn_limit` Turns pass first.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct LevelGoals {
+    pub conditions: Vec<GoalCondition>,
+    pub turn_limit: Option<usize>,
+    pub next_scene: Option<String>,
+    pub restart_on_failure: bool,
+}
+
+impl LevelGoals {
+    /// Checks for conditions which could never mean anything. An `AllOn` with the
+    /// same mover and target is met by every mover just by being on the Grid.
+    pub fn validate(&self) -> Result<(), Error> {
+        for condition in self.conditions.iter() {
+            if let GoalCondition::AllOn { mover, target } = condition {
+                if mover == target {
+                    bail!("All On has {:?} as both its mover and its target!", mover);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SingletonBounds for LevelGoals {
+    fn entity_inspector(&mut self, inspector_parameters: InspectorParameters<'_, '_>) {
+        let InspectorParameters { uid, ui, .. } = inspector_parameters;
+
+        // Conditions
+        let mut remove = None;
+        for (i, condition) in self.conditions.iter_mut().enumerate() {
+            let condition_uid = format!("{}{}", uid, i);
+
+            match condition {
+                GoalCondition::AllOn { mover, target } => {
+                    ui.text(imgui::im_str!("All On"));
+                    if let Some(new_mover) =
+                        imgui_system::typed_enum_selection(ui, mover, &format!("{}mover", condition_uid))
+                    {
+                        *mover = new_mover;
+                    }
+
+                    if let Some(new_target) =
+                        imgui_system::typed_enum_selection(ui, target, &format!("{}target", condition_uid))
+                    {
+                        *target = new_target;
+                    }
+                }
+
+                GoalCondition::EventRaised(event_name) => {
+                    let mut name = imgui::ImString::new(event_name.clone());
+                    if ui
+                        .input_text(&imgui::im_str!("Event Raised##{}", condition_uid), &mut name)
+                        .resize_buffer(true)
+                        .build()
+                    {
+                        *event_name = name.to_string();
+                    }
+                }
+            }
+
+            if ui.button(&imgui::im_str!("Remove Condition##{}", condition_uid), [0.0, 0.0]) {
+                remove = Some(i);
+            }
+            ui.separator();
+        }
+
+        if let Some(remove) = remove {
+            self.conditions.remove(remove);
+        }
+
+        if ui.button(&imgui::im_str!("Add All On##{}", uid), [0.0, 0.0]) {
+            self.conditions.push(GoalCondition::AllOn {
+                mover: GridType::Player,
+                target: GridType::Flag,
+            });
+        }
+        ui.same_line(0.0);
+        if ui.button(&imgui::im_str!("Add Event Raised##{}", uid), [0.0, 0.0]) {
+            self.conditions.push(GoalCondition::EventRaised(String::new()));
+        }
+
+        if let Err(e) = self.validate() {
+            ui.text_colored(imgui_system::red_warning_color(), &imgui::im_str!("{}", e));
+        }
+
+        // Turn Limit
+        let mut has_turn_limit = self.turn_limit.is_some();
+        if ui.checkbox(&imgui::im_str!("Turn Limit##{}", uid), &mut has_turn_limit) {
+            self.turn_limit = if has_turn_limit { Some(20) } else { None };
+        }
+
+        if let Some(turn_limit) = &mut self.turn_limit {
+            imgui_system::input_usize(ui, &imgui::im_str!("Turns##{}", uid), turn_limit);
+        }
+
+        // Next Scene
+        let mut next_scene = imgui::ImString::new(self.next_scene.clone().unwrap_or_default());
+        if ui
+            .input_text(&imgui::im_str!("Next Scene##{}", uid), &mut next_scene)
+            .resize_buffer(true)
+            .build()
+        {
+            self.next_scene = if next_scene.is_empty() {
+                None
+            } else {
+                Some(next_scene.to_string())
+            };
+        }
+
+        ui.checkbox(
+            &imgui::im_str!("Restart on Failure##{}", uid),
+            &mut self.restart_on_failure,
+        );
+    }
+}
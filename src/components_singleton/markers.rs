@@ -3,5 +3,8 @@ use strum_macros::Display;
 #[derive(Debug, Serialize, Display, Deserialize, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Marker {
     Camera,
+    Grid,
+    Level,
+    Music,
     ExemptFromGrid,
 }
@@ -0,0 +1,46 @@
+use super::{imgui_system, InspectorParameters, SingletonBounds, SoundName};
+
+/// The music which loops while a Scene is loaded. Switching to a Scene with
+/// a different track crossfades over the new Scene's `fade_time`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SceneMusic {
+    /// The name of a sound in `assets/audio`.
+    pub track: Option<SoundName>,
+    pub volume: f32,
+    /// Seconds to fade between the last Scene's track and this one.
+    pub fade_time: f32,
+}
+
+impl Default for SceneMusic {
+    fn default() -> Self {
+        Self {
+            track: None,
+            volume: 1.0,
+            fade_time: 1.0,
+        }
+    }
+}
+
+impl SingletonBounds for SceneMusic {
+    fn entity_inspector(&mut self, inspector_parameters: InspectorParameters<'_, '_>) {
+        let InspectorParameters { uid, ui, .. } = inspector_parameters;
+
+        if let Some(new_track) = imgui_system::sound_name_input(ui, &self.track, "Track", uid) {
+            self.track = new_track;
+        }
+
+        ui.drag_float(&imgui::im_str!("Volume##{}", uid), &mut self.volume)
+            .min(0.0)
+            .max(1.0)
+            .speed(0.01)
+            .build();
+
+        if ui
+            .input_float(&imgui::im_str!("Fade Time##{}", uid), &mut self.fade_time)
+            .build()
+        {
+            self.fade_time = self.fade_time.max(0.0);
+        }
+    }
+}
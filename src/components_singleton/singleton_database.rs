@@ -1,6 +1,6 @@
 use super::{
-    serialization_util, Camera, Entity, Marker, RenderingUtility, ResourcesDatabase, SingletonBounds,
-    SingletonComponent,
+    serialization_util, Camera, Entity, GridInfo, LevelGoals, Marker, RenderingUtility, ResourcesDatabase,
+    SceneMusic, SingletonBounds, SingletonComponent,
 };
 use anyhow::{Error, Result as AnyResult};
 use std::collections::HashMap;
@@ -8,8 +8,12 @@ use std::collections::HashMap;
 pub type AssociatedEntityMap = HashMap<Marker, Entity>;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SingletonDatabase {
     pub camera: SingletonComponent<Camera>,
+    pub grid: SingletonComponent<GridInfo>,
+    pub level: SingletonComponent<LevelGoals>,
+    pub music: SingletonComponent<SceneMusic>,
     #[serde(skip)]
     pub rendering_utility: RenderingUtility,
     #[serde(skip)]
@@ -65,6 +69,9 @@ impl Default for SingletonDatabase {
         SingletonDatabase {
             // @update_singletons
             camera: SingletonComponent::new(Marker::Camera, Camera::default()),
+            grid: SingletonComponent::new(Marker::Grid, GridInfo::default()),
+            level: SingletonComponent::new(Marker::Level, LevelGoals::default()),
+            music: SingletonComponent::new(Marker::Music, SceneMusic::default()),
             rendering_utility: RenderingUtility::default(),
             associated_entities: HashMap::new(),
         }
@@ -1,11 +1,12 @@
 use super::{
+    command_buffer::{self, CommandBuffer},
     components::{ComponentDatabase, Entity},
     components_singleton::SingletonDatabase,
     entities::EntityAllocator,
     hardware_interfaces::HardwareInterface,
     resources::{PrefabMap, ResourcesDatabase},
     systems::*,
-    ActionMap, GameWorldDrawCommands,
+    GameWorldDrawCommands,
 };
 use anyhow::Error;
 
@@ -49,6 +50,14 @@ impl Ecs {
 
         // tilemap_system::initialize_tilemaps(&mut self.component_database.tilemaps, &resources.tilesets);
 
+        self.initialize_board(grid);
+
+        Ok(())
+    }
+
+    /// The part of GameStart which needs no runtime resources. Headless tools run this
+    /// too (see `scene_system::load_scene_headless`), so they start from the same board.
+    pub fn initialize_board(&mut self, grid: &mut grid_system::Grid) {
         player_system::initialize_players(
             &mut self.component_database.players,
             &mut self.component_database.sprites,
@@ -60,28 +69,6 @@ impl Ecs {
             grid,
             &self.singleton_database.associated_entities,
         );
-
-        Ok(())
-    }
-
-    pub fn update(&mut self, grid: &mut grid_system::Grid, actions: &ActionMap) -> Result<(), Error> {
-        // // Player Stuff
-        player_system::player_update(
-            &mut self.component_database.players,
-            &mut self.component_database.sprites,
-            &mut self.component_database.velocities,
-            actions,
-        );
-
-        // Movement Stuff
-        grid_system::update_grid_positions(self, grid);
-
-        Ok(())
-    }
-
-    pub fn update_resources(&mut self, resources: &ResourcesDatabase, delta_time: f32) {
-        sprite_system::update_sprites(&mut self.component_database.sprites, resources, delta_time);
-        cross_cutting_system::cross_cutting_system(self, resources);
     }
 
     pub fn render<'a, 'b>(
@@ -109,7 +96,8 @@ impl Ecs {
 impl Ecs {
     /// This is the standard method to create a new Entity in the Ecs. Try to
     /// always use this one. The returned Entity is the ID, or index, of the new
-    /// entity.
+    /// entity. Systems should push into a CommandBuffer instead, since this
+    /// can't be done while they're iterating.
     pub fn create_entity(&mut self) -> Entity {
         Ecs::create_entity_raw(
             &mut self.component_database,
@@ -151,19 +139,30 @@ impl Ecs {
         entity
     }
 
-    pub fn remove_entity(&mut self, entity_to_delete: &Entity) -> bool {
-        Ecs::remove_entity_raw(
-            &mut self.entity_allocator,
-            &mut self.entities,
-            &mut self.component_database,
-            entity_to_delete,
-        )
+    /// Destroys the Entity and everything under it in the scene graph, running their
+    /// `on_removed` hooks. Like `create_entity`, systems should use a CommandBuffer instead.
+    pub fn remove_entity(&mut self, entity_to_delete: &Entity, prefabs: &PrefabMap) -> bool {
+        if self.entity_allocator.is_live(*entity_to_delete) == false {
+            return false;
+        }
+
+        let mut commands = CommandBuffer::new();
+        commands.destroy_entity(*entity_to_delete);
+        commands.apply(self, prefabs);
+
+        true
     }
 
-    pub fn clone_entity(&mut self, original: &Entity) -> Entity {
+    /// Makes a new Entity with a clone of each of the original's components, and runs
+    /// their `on_added` hooks.
+    pub fn clone_entity(&mut self, original: &Entity, prefabs: &PrefabMap) -> Entity {
         let new_entity = self.create_entity();
         self.component_database.clone_components(original, &new_entity);
 
+        let mut hook_commands = CommandBuffer::new();
+        command_buffer::run_added_hooks(&mut self.component_database, new_entity, &mut hook_commands);
+        hook_commands.apply(self, prefabs);
+
         new_entity
     }
 }
@@ -1,10 +1,12 @@
 mod generational_index;
 mod generational_index_array;
 mod generational_index_value;
+mod join;
 
 pub use generational_index::*;
 pub use generational_index_array::*;
 pub use generational_index_value::*;
+pub use join::*;
 
 pub type Entity = generational_index::GenerationalIndex;
 pub type EntityAllocator = GenerationalIndexAllocator;
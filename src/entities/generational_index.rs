@@ -100,12 +100,13 @@ impl GenerationalIndexAllocator {
         }
     }
 
-    #[allow(dead_code)]
+    /// Returns true if this exact generation of the index is allocated.
     pub fn is_live(&self, index: GenerationalIndex) -> bool {
         if index.index >= self.entries.len() {
             false
         } else {
-            self.entries[index.index].is_live
+            let entry = &self.entries[index.index];
+            entry.is_live && entry.generation == index.generation
         }
     }
 }
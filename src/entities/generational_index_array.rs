@@ -1,4 +1,5 @@
-use super::{GenerationalIndex, GenerationalIndexValue};
+use super::{GenerationalIndex, GenerationalIndexValue, StorageKind};
+use std::iter::{Peekable, Zip};
 
 #[derive(Serialize, Deserialize, Default, Debug)]
 struct ArrayEntry<T: GenerationalIndexValue> {
@@ -6,68 +7,174 @@ struct ArrayEntry<T: GenerationalIndexValue> {
     generation: u64,
 }
 
-// An array from GenerationalIndex to some Value T.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct GenerationalIndexArray<T: GenerationalIndexValue>(Vec<Option<ArrayEntry<T>>>);
+enum Entries<T: GenerationalIndexValue> {
+    Sparse(Vec<Option<ArrayEntry<T>>>),
+    Dense {
+        /// The position in `values` for every entity index.
+        positions: Vec<Option<usize>>,
+        /// Kept in order of their entity index, so they can be walked alongside `positions`.
+        values: Vec<ArrayEntry<T>>,
+        /// The entity index for every position in `values`.
+        owners: Vec<usize>,
+    },
+}
+
+// An array from GenerationalIndex to some Value T. Its layout is picked by
+// `T::storage()` -- see `StorageKind`. Either way, there's a slot for every
+// entity index, so `slots` lines up between arrays.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GenerationalIndexArray<T: GenerationalIndexValue>(Entries<T>);
 
 impl<T: GenerationalIndexValue> GenerationalIndexArray<T> {
     pub fn new() -> Self {
-        Self(Vec::new())
+        Self::with_storage(T::storage())
+    }
+
+    pub fn with_storage(storage: StorageKind) -> Self {
+        Self(match storage {
+            StorageKind::Sparse => Entries::Sparse(Vec::new()),
+            StorageKind::Dense => Entries::Dense {
+                positions: Vec::new(),
+                values: Vec::new(),
+                owners: Vec::new(),
+            },
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn storage(&self) -> StorageKind {
+        match &self.0 {
+            Entries::Sparse(_) => StorageKind::Sparse,
+            Entries::Dense { .. } => StorageKind::Dense,
+        }
     }
 
     /// Returns a mutable Iterator over the internal members of the Vec.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
-        self.0.iter_mut().flat_map(|opt_ent| {
-            let value = &mut opt_ent.as_mut()?.value;
-            if value.is_active() {
-                Some(value)
-            } else {
-                None
-            }
-        })
+        let (sparse, dense) = match &mut self.0 {
+            Entries::Sparse(entries) => (Some(entries.iter_mut().flatten()), None),
+            Entries::Dense { values, .. } => (None, Some(values.iter_mut())),
+        };
+
+        sparse
+            .into_iter()
+            .flatten()
+            .chain(dense.into_iter().flatten())
+            .map(|ent| &mut ent.value)
+            .filter(|value| value.is_active())
     }
 
     /// Returns an immutable Iterator over the internal members of the Vec.
     pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.0.iter().flat_map(|opt_ent| {
-            let value = &opt_ent.as_ref()?.value;
-            if value.is_active() {
-                Some(value)
-            } else {
-                None
-            }
+        let (sparse, dense) = match &self.0 {
+            Entries::Sparse(entries) => (Some(entries.iter().flatten()), None),
+            Entries::Dense { values, .. } => (None, Some(values.iter())),
+        };
+
+        sparse
+            .into_iter()
+            .flatten()
+            .chain(dense.into_iter().flatten())
+            .map(|ent| &ent.value)
+            .filter(|value| value.is_active())
+    }
+
+    /// Returns an Iterator over every slot in the Vec, giving None for slots without
+    /// an active value. Every ComponentList has a slot for every entity, so these
+    /// line up with each other, which is what `Join` is built on.
+    pub fn slots(&self) -> Slots<'_, T> {
+        Slots(match &self.0 {
+            Entries::Sparse(entries) => SlotsInner::Sparse(entries.iter()),
+            Entries::Dense {
+                positions, values, ..
+            } => SlotsInner::Dense(positions.iter(), values),
+        })
+    }
+
+    /// Returns a mutable Iterator over every slot in the Vec. See `slots`.
+    pub fn slots_mut(&mut self) -> SlotsMut<'_, T> {
+        SlotsMut(match &mut self.0 {
+            Entries::Sparse(entries) => SlotsMutInner::Sparse(entries.iter_mut()),
+            Entries::Dense {
+                positions,
+                values,
+                owners,
+            } => SlotsMutInner::Dense {
+                index: 0,
+                len: positions.len(),
+                values: owners.iter().zip(values.iter_mut()).peekable(),
+            },
         })
     }
 
     /// Set the value for some generational index.  May overwrite past generation
     /// values.
     pub fn set(&mut self, index: &GenerationalIndex, value: T) {
-        self.0[index.index] = Some(ArrayEntry {
+        let entry = ArrayEntry {
             value,
             generation: index.generation,
-        });
+        };
+
+        match &mut self.0 {
+            Entries::Sparse(entries) => {
+                entries[index.index] = Some(entry);
+            }
+            Entries::Dense {
+                positions,
+                values,
+                owners,
+            } => match positions[index.index] {
+                Some(position) => values[position] = entry,
+                None => {
+                    let position = owners.binary_search(&index.index).unwrap_err();
+                    values.insert(position, entry);
+                    owners.insert(position, index.index);
+
+                    for (moved_position, owner) in owners.iter().enumerate().skip(position) {
+                        positions[*owner] = Some(moved_position);
+                    }
+                }
+            },
+        }
     }
 
     /// Adds a new component for a new entity to the end of the List. Don't use this
     /// very commonly -- this is for adding new entities, essentially.
     pub fn expand_list(&mut self) {
-        self.0.push(None);
+        match &mut self.0 {
+            Entries::Sparse(entries) => entries.push(None),
+            Entries::Dense { positions, .. } => positions.push(None),
+        }
     }
 
     /// Unsets the value for some generational index. Returns true if succesfully
-    /// unset.
+    /// unset. Dense arrays move every value after it back one to fill the hole.
     pub fn unset(&mut self, index: &GenerationalIndex) -> bool {
-        let ret = &self.0[index.index];
-        if let Some(ret) = ret {
-            if ret.generation == index.generation {
-                self.0[index.index] = None;
-                true
-            } else {
-                false
+        if self.contains(index) == false {
+            return false;
+        }
+
+        match &mut self.0 {
+            Entries::Sparse(entries) => {
+                entries[index.index] = None;
+            }
+            Entries::Dense {
+                positions,
+                values,
+                owners,
+            } => {
+                let position = positions[index.index].take().unwrap();
+                values.remove(position);
+                owners.remove(position);
+
+                for (moved_position, owner) in owners.iter().enumerate().skip(position) {
+                    positions[*owner] = Some(moved_position);
+                }
             }
-        } else {
-            false
         }
+
+        true
     }
 
     // Checks if the desired index points to a valid location. Merely shorthand
@@ -78,7 +185,13 @@ impl<T: GenerationalIndexValue> GenerationalIndexArray<T> {
 
     // Gets an immutable reference to the contained value, if it exists.
     pub fn get(&self, index: &GenerationalIndex) -> Option<&T> {
-        let ret = &self.0[index.index];
+        let ret = match &self.0 {
+            Entries::Sparse(entries) => entries[index.index].as_ref(),
+            Entries::Dense {
+                positions, values, ..
+            } => positions[index.index].map(|p| &values[p]),
+        };
+
         if let Some(ret) = ret {
             if ret.generation == index.generation {
                 Some(&ret.value)
@@ -92,7 +205,13 @@ impl<T: GenerationalIndexValue> GenerationalIndexArray<T> {
 
     /// Gets a mutable reference to the contained value, if it exists.
     pub fn get_mut(&mut self, index: &GenerationalIndex) -> Option<&mut T> {
-        let ret = &mut self.0[index.index];
+        let ret = match &mut self.0 {
+            Entries::Sparse(entries) => entries[index.index].as_mut(),
+            Entries::Dense {
+                positions, values, ..
+            } => positions[index.index].map(move |p| &mut values[p]),
+        };
+
         if let Some(ret) = ret {
             if ret.generation == index.generation {
                 Some(&mut ret.value)
@@ -110,3 +229,82 @@ impl<T: GenerationalIndexValue> Default for GenerationalIndexArray<T> {
         Self::new()
     }
 }
+
+enum SlotsInner<'a, T: GenerationalIndexValue> {
+    Sparse(std::slice::Iter<'a, Option<ArrayEntry<T>>>),
+    Dense(std::slice::Iter<'a, Option<usize>>, &'a [ArrayEntry<T>]),
+}
+
+pub struct Slots<'a, T: GenerationalIndexValue>(SlotsInner<'a, T>);
+
+impl<'a, T: GenerationalIndexValue> Iterator for Slots<'a, T> {
+    type Item = Option<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let opt_ent = match &mut self.0 {
+            SlotsInner::Sparse(entries) => entries.next()?.as_ref(),
+            SlotsInner::Dense(positions, values) => {
+                let values: &'a [ArrayEntry<T>] = values;
+                positions.next()?.map(|p| &values[p])
+            }
+        };
+
+        Some(opt_ent.map(|ent| &ent.value).filter(|value| value.is_active()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            SlotsInner::Sparse(entries) => entries.size_hint(),
+            SlotsInner::Dense(positions, _) => positions.size_hint(),
+        }
+    }
+}
+
+enum SlotsMutInner<'a, T: GenerationalIndexValue> {
+    Sparse(std::slice::IterMut<'a, Option<ArrayEntry<T>>>),
+    /// The values are in entity order, so each one is handed out once we reach its owner.
+    Dense {
+        index: usize,
+        len: usize,
+        values: Peekable<Zip<std::slice::Iter<'a, usize>, std::slice::IterMut<'a, ArrayEntry<T>>>>,
+    },
+}
+
+pub struct SlotsMut<'a, T: GenerationalIndexValue>(SlotsMutInner<'a, T>);
+
+impl<'a, T: GenerationalIndexValue> Iterator for SlotsMut<'a, T> {
+    type Item = Option<&'a mut T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let opt_ent = match &mut self.0 {
+            SlotsMutInner::Sparse(entries) => entries.next()?.as_mut(),
+            SlotsMutInner::Dense { index, len, values } => {
+                if *index == *len {
+                    return None;
+                }
+
+                let owned_here = values.peek().map_or(false, |(owner, _)| **owner == *index);
+                *index += 1;
+
+                if owned_here {
+                    values.next().map(|(_, value)| value)
+                } else {
+                    None
+                }
+            }
+        };
+
+        Some(
+            opt_ent
+                .map(|ent| &mut ent.value)
+                .filter(|value| value.is_active()),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            SlotsMutInner::Sparse(entries) => entries.size_hint(),
+            SlotsMutInner::Dense { index, len, .. } => (len - index, Some(len - index)),
+        }
+    }
+}
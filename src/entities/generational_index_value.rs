@@ -1,3 +1,26 @@
 pub trait GenerationalIndexValue {
     fn is_active(&self) -> bool;
+
+    /// How a GenerationalIndexArray of this type lays out its values.
+    fn storage() -> StorageKind
+    where
+        Self: Sized,
+    {
+        StorageKind::Sparse
+    }
+}
+
+/// The layout of a GenerationalIndexArray.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageKind {
+    /// A slot holding the value for every entity index. Lookups are a single index,
+    /// but iterating walks every entity which has ever been allocated.
+    Sparse,
+    /// Values are packed together, with a slot per entity index which only holds
+    /// the position of its value. Iterating only walks the values there are, so
+    /// this suits types which are walked often but which plenty of entities don't
+    /// have, like the Sprites and GridObjects of a Scene's tiles. The values are kept
+    /// in entity order, so adding or removing one moves every value after it -- and
+    /// nothing may hold a pointer into a Dense array.
+    Dense,
 }
@@ -0,0 +1,236 @@
+use super::{generational_index_array, Component, ComponentBounds, ComponentList, Entity};
+
+/// A query over one or more ComponentLists, which walks every entity with all of
+/// the components asked for. Shared and mutable lists can be mixed freely, and
+/// `Maybe` makes a component optional:
+///
+/// ```ignore
+/// for (entity, (transform, grid_object, velocity)) in
+///     (&mut cd.transforms, &cd.grid_objects, Maybe(&cd.velocities)).join()
+/// {
+///     ...
+/// }
+/// ```
+///
+/// Every ComponentList has a slot for every entity, so this just zips their slots
+/// together -- there are no lookups, and nothing is allocated.
+/// Inactive components are skipped, the same as with `iter`.
+pub trait Join: Sized {
+    type Item;
+    type Slots: Iterator<Item = Slot<Self::Item>>;
+
+    /// Every slot in the query, one per entity index.
+    fn slots(self) -> Self::Slots;
+
+    /// Every entity with all of the components in the query.
+    fn join(self) -> JoinIter<Self::Slots> {
+        JoinIter(self.slots())
+    }
+}
+
+/// What a query has at one entity index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slot<I> {
+    /// Something the query needs is missing.
+    Empty,
+    /// The query matched this entity.
+    Found(Entity, I),
+    /// The query only has `Maybe` components, which don't say which entity
+    /// is here. A tuple with another component in it fills in the entity.
+    Unclaimed(I),
+}
+
+pub struct JoinIter<S>(S);
+
+impl<I, S: Iterator<Item = Slot<I>>> Iterator for JoinIter<S> {
+    type Item = (Entity, I);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Slot::Found(entity, item) = self.0.next()? {
+                return Some((entity, item));
+            }
+        }
+    }
+}
+
+pub struct ComponentSlots<'a, T: ComponentBounds + Clone>(generational_index_array::Slots<'a, Component<T>>);
+
+impl<'a, T: ComponentBounds + Clone> Iterator for ComponentSlots<'a, T> {
+    type Item = Slot<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.0.next()? {
+            Some(component) => Slot::Found(component.entity_id(), component.inner()),
+            None => Slot::Empty,
+        })
+    }
+}
+
+impl<'a, T: ComponentBounds + Clone> Join for &'a ComponentList<T> {
+    type Item = &'a T;
+    type Slots = ComponentSlots<'a, T>;
+
+    fn slots(self) -> Self::Slots {
+        ComponentSlots(self.slots())
+    }
+}
+
+pub struct ComponentSlotsMut<'a, T: ComponentBounds + Clone>(
+    generational_index_array::SlotsMut<'a, Component<T>>,
+);
+
+impl<'a, T: ComponentBounds + Clone> Iterator for ComponentSlotsMut<'a, T> {
+    type Item = Slot<&'a mut T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.0.next()? {
+            Some(component) => Slot::Found(component.entity_id(), component.inner_mut()),
+            None => Slot::Empty,
+        })
+    }
+}
+
+impl<'a, T: ComponentBounds + Clone> Join for &'a mut ComponentList<T> {
+    type Item = &'a mut T;
+    type Slots = ComponentSlotsMut<'a, T>;
+
+    fn slots(self) -> Self::Slots {
+        ComponentSlotsMut(self.slots_mut())
+    }
+}
+
+/// Makes part of a query optional, giving None where the entity doesn't have it.
+pub struct Maybe<J>(pub J);
+
+pub struct MaybeSlots<S>(S);
+
+impl<I, S: Iterator<Item = Slot<I>>> Iterator for MaybeSlots<S> {
+    type Item = Slot<Option<I>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.0.next()? {
+            Slot::Found(entity, item) => Slot::Found(entity, Some(item)),
+            Slot::Unclaimed(item) => Slot::Unclaimed(Some(item)),
+            Slot::Empty => Slot::Unclaimed(None),
+        })
+    }
+}
+
+impl<J: Join> Join for Maybe<J> {
+    type Item = Option<J::Item>;
+    type Slots = MaybeSlots<J::Slots>;
+
+    fn slots(self) -> Self::Slots {
+        MaybeSlots(self.0.slots())
+    }
+}
+
+/// The slots of a tuple of queries. An entity is only found if every query
+/// has it, at the same generation.
+pub struct JoinSlots<S>(S);
+
+macro_rules! impl_join_for_tuple {
+    ($(($join:ident, $item:ident, $slot:ident)),+) => {
+        impl<$($join: Join),+> Join for ($($join,)+) {
+            type Item = ($($join::Item,)+);
+            type Slots = JoinSlots<($($join::Slots,)+)>;
+
+            fn slots(self) -> Self::Slots {
+                let ($($slot,)+) = self;
+                JoinSlots(($($slot.slots(),)+))
+            }
+        }
+
+        impl<$($join, $item),+> Iterator for JoinSlots<($($join,)+)>
+        where
+            $($join: Iterator<Item = Slot<$item>>),+
+        {
+            type Item = Slot<($($item,)+)>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let ($($slot,)+) = &mut self.0;
+
+                // Every list is the same length, so we advance them all before looking at any.
+                $(let $slot = $slot.next()?;)+
+
+                let mut entity: Option<Entity> = None;
+                $(
+                    let $slot = match $slot {
+                        Slot::Found(this_entity, item) if entity.map_or(true, |e| e == this_entity) => {
+                            entity = Some(this_entity);
+                            item
+                        }
+                        Slot::Unclaimed(item) => item,
+                        _ => return Some(Slot::Empty),
+                    };
+                )+
+
+                Some(match entity {
+                    Some(entity) => Slot::Found(entity, ($($slot,)+)),
+                    None => Slot::Unclaimed(($($slot,)+)),
+                })
+            }
+        }
+    };
+}
+
+impl_join_for_tuple!((A, AItem, a), (B, BItem, b));
+impl_join_for_tuple!((A, AItem, a), (B, BItem, b), (C, CItem, c));
+impl_join_for_tuple!((A, AItem, a), (B, BItem, b), (C, CItem, c), (D, DItem, d));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityAllocator, Follow, Name, Sprite, StorageKind};
+
+    #[test]
+    fn mutable_joins_over_dense_lists_reach_each_entity_once() {
+        let mut entity_allocator = EntityAllocator::new();
+        let mut names = ComponentList::<Name>::new();
+        let mut sprites = ComponentList::<Sprite>::new();
+        let mut follows = ComponentList::<Follow>::new();
+        assert_eq!(sprites.storage(), StorageKind::Dense);
+        assert_eq!(follows.storage(), StorageKind::Dense);
+
+        let entities: Vec<Entity> = (0..6).map(|_| entity_allocator.allocate()).collect();
+        for entity in entities.iter() {
+            names.expand_list();
+            sprites.expand_list();
+            follows.expand_list();
+            names.set_component(entity, Name::default());
+        }
+
+        // Set them out of order, and take one out, so the values aren't in entity order
+        for entity in entities.iter().rev() {
+            sprites.set_component(entity, Sprite::default());
+        }
+        sprites.unset(&entities[4]);
+        for entity in entities.iter().step_by(2) {
+            follows.set_component(entity, Follow::default());
+        }
+
+        let mut visited = vec![];
+        for (entity, (sprite, follow, _)) in (&mut sprites, &mut follows, &names).join() {
+            sprite.running_data.current_frame = entity.index() + 1;
+            follow.offset.x += 1.0;
+            visited.push(entity);
+        }
+
+        assert_eq!(visited, vec![entities[0], entities[2]]);
+        for entity in entities.iter() {
+            let current_frame = sprites
+                .get(entity)
+                .map(|sprite| sprite.inner().running_data.current_frame);
+            let offset = follows.get(entity).map(|follow| follow.inner().offset.x);
+
+            if visited.contains(entity) {
+                assert_eq!(current_frame, Some(entity.index() + 1));
+                assert_eq!(offset, Some(1.0));
+            } else {
+                assert_eq!(current_frame.unwrap_or_default(), 0);
+                assert_eq!(offset.unwrap_or_default(), 0.0);
+            }
+        }
+    }
+}
@@ -1,12 +1,17 @@
 pub use super::*;
 
+mod gamepad;
 mod hardware_interface;
 mod input;
 mod renderer;
 mod sound_player;
 mod window;
 
-pub use input::{Input, KeyboardInput, MouseButton, MouseInput};
+pub use gamepad::{
+    default_gamepad_source, GamepadAxis, GamepadButton, GamepadEvent, GamepadInput, GamepadSource,
+    GilrsGamepadSource, MockGamepadSource,
+};
+pub use input::{Input, KeyboardInput, MouseButton, MouseInput, MOUSE_BUTTON_COUNT};
 pub use renderer::{
     BufferBundle, DrawingError, ImguiPushConstants, LoadedImage, PipelineBundle, RendererComponent,
     RendererCreationError, StandardPushConstants, StandardQuad, StandardQuadFactory, StandardTexture,
@@ -14,5 +19,5 @@ pub use renderer::{
 };
 
 pub use hardware_interface::HardwareInterface;
-// pub use sound_player::SoundPlayer;
+pub use sound_player::SoundPlayer;
 pub use window::Window;
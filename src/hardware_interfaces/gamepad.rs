@@ -0,0 +1,395 @@
+use super::{cardinals::CardinalPrime, Vec2};
+use anyhow::Error;
+use std::collections::VecDeque;
+
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum_macros::EnumIter,
+    strum_macros::Display,
+)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GamepadEvent {
+    Connected,
+    Disconnected,
+    ButtonPressed(GamepadButton),
+    ButtonReleased(GamepadButton),
+    /// Axes go from -1.0 to 1.0, with up and right being positive.
+    AxisChanged(GamepadAxis, f32),
+}
+
+/// Where GamepadEvents come from. Every connected gamepad feeds the same
+/// GamepadInput, so any of them can be used to play.
+pub trait GamepadSource {
+    fn next_event(&mut self) -> Option<GamepadEvent>;
+}
+
+/// A GamepadSource which only gives back the events pushed into it. This is
+/// used when there's no gamepad backend, and to fake a gamepad without hardware.
+#[derive(Debug, Default)]
+pub struct MockGamepadSource {
+    pub events: VecDeque<GamepadEvent>,
+}
+
+#[allow(dead_code)]
+impl MockGamepadSource {
+    pub fn push(&mut self, event: GamepadEvent) {
+        self.events.push_back(event);
+    }
+
+    pub fn tap(&mut self, button: GamepadButton) {
+        self.push(GamepadEvent::ButtonPressed(button));
+        self.push(GamepadEvent::ButtonReleased(button));
+    }
+
+    pub fn move_stick(&mut self, x: f32, y: f32) {
+        self.push(GamepadEvent::AxisChanged(GamepadAxis::LeftStickX, x));
+        self.push(GamepadEvent::AxisChanged(GamepadAxis::LeftStickY, y));
+    }
+}
+
+impl GamepadSource for MockGamepadSource {
+    fn next_event(&mut self) -> Option<GamepadEvent> {
+        self.events.pop_front()
+    }
+}
+
+pub struct GilrsGamepadSource {
+    gilrs: gilrs::Gilrs,
+}
+
+impl GilrsGamepadSource {
+    pub fn new() -> Result<Self, Error> {
+        let gilrs = gilrs::Gilrs::new().map_err(|e| anyhow!("Couldn't start Gilrs: {}", e))?;
+
+        Ok(Self { gilrs })
+    }
+
+    fn translate_button(button: gilrs::Button) -> Option<GamepadButton> {
+        use gilrs::Button;
+
+        Some(match button {
+            Button::South => GamepadButton::South,
+            Button::East => GamepadButton::East,
+            Button::North => GamepadButton::North,
+            Button::West => GamepadButton::West,
+            Button::LeftTrigger => GamepadButton::LeftBumper,
+            Button::RightTrigger => GamepadButton::RightBumper,
+            Button::Select => GamepadButton::Select,
+            Button::Start => GamepadButton::Start,
+            Button::DPadUp => GamepadButton::DPadUp,
+            Button::DPadDown => GamepadButton::DPadDown,
+            Button::DPadLeft => GamepadButton::DPadLeft,
+            Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+}
+
+impl GamepadSource for GilrsGamepadSource {
+    fn next_event(&mut self) -> Option<GamepadEvent> {
+        use gilrs::{Axis, EventType};
+
+        // We skip over anything we don't understand
+        while let Some(gilrs::Event { event, .. }) = self.gilrs.next_event() {
+            let gamepad_event = match event {
+                EventType::Connected => Some(GamepadEvent::Connected),
+                EventType::Disconnected => Some(GamepadEvent::Disconnected),
+                EventType::ButtonPressed(button, _) => {
+                    Self::translate_button(button).map(GamepadEvent::ButtonPressed)
+                }
+                EventType::ButtonReleased(button, _) => {
+                    Self::translate_button(button).map(GamepadEvent::ButtonReleased)
+                }
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => {
+                    Some(GamepadEvent::AxisChanged(GamepadAxis::LeftStickX, value))
+                }
+                EventType::AxisChanged(Axis::LeftStickY, value, _) => {
+                    Some(GamepadEvent::AxisChanged(GamepadAxis::LeftStickY, value))
+                }
+                _ => None,
+            };
+
+            if gamepad_event.is_some() {
+                return gamepad_event;
+            }
+        }
+
+        None
+    }
+}
+
+/// Creates a Gilrs backed GamepadSource, or a MockGamepadSource which never
+/// has any events if Gilrs can't start.
+pub fn default_gamepad_source() -> Box<dyn GamepadSource> {
+    match GilrsGamepadSource::new() {
+        Ok(gilrs_source) => Box::new(gilrs_source),
+        Err(e) => {
+            error!("No gamepad support! {}", e);
+            Box::new(MockGamepadSource::default())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GamepadInput {
+    pub connected: bool,
+    pub pressed_buttons: Vec<GamepadButton>,
+    pub held_buttons: Vec<GamepadButton>,
+    pub released_buttons: Vec<GamepadButton>,
+    pub left_stick: Vec2,
+    /// How far the stick has to be pushed before it counts as a direction.
+    pub deadzone: f32,
+    stick_direction: Option<CardinalPrime>,
+    stick_pressed: Option<CardinalPrime>,
+}
+
+impl Default for GamepadInput {
+    fn default() -> Self {
+        Self {
+            connected: false,
+            pressed_buttons: vec![],
+            held_buttons: vec![],
+            released_buttons: vec![],
+            left_stick: Vec2::ZERO,
+            deadzone: Self::DEFAULT_DEADZONE,
+            stick_direction: None,
+            stick_pressed: None,
+        }
+    }
+}
+
+impl GamepadInput {
+    pub const DEFAULT_DEADZONE: f32 = 0.5;
+
+    pub fn clear(&mut self) {
+        self.pressed_buttons.clear();
+        self.released_buttons.clear();
+        self.stick_pressed = None;
+    }
+
+    pub fn record_event(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected => {
+                info!("Gamepad connected!");
+                self.connected = true;
+            }
+
+            GamepadEvent::Disconnected => {
+                info!("Gamepad disconnected!");
+                self.connected = false;
+                self.released_buttons.append(&mut self.held_buttons);
+                self.left_stick = Vec2::ZERO;
+            }
+
+            GamepadEvent::ButtonPressed(button) => {
+                if self.held_buttons.contains(&button) == false {
+                    self.pressed_buttons.push(button);
+                    self.held_buttons.push(button);
+                }
+            }
+
+            GamepadEvent::ButtonReleased(button) => {
+                if let Some(pos) = self.held_buttons.iter().position(|&held| held == button) {
+                    self.held_buttons.remove(pos);
+                    self.released_buttons.push(button);
+                }
+            }
+
+            GamepadEvent::AxisChanged(GamepadAxis::LeftStickX, value) => {
+                self.left_stick.x = value;
+            }
+
+            GamepadEvent::AxisChanged(GamepadAxis::LeftStickY, value) => {
+                self.left_stick.y = value;
+            }
+        }
+    }
+
+    /// Works out which way the stick is pushed. Call this once all of a frame's
+    /// events are recorded, so that `stick_pressed` is only set for one frame.
+    pub fn update_stick(&mut self) {
+        let x = self.left_stick.x;
+        let y = self.left_stick.y;
+
+        let new_direction = if x.abs().max(y.abs()) < self.deadzone {
+            None
+        } else if x.abs() >= y.abs() {
+            Some(if x > 0.0 {
+                CardinalPrime::Right
+            } else {
+                CardinalPrime::Left
+            })
+        } else {
+            Some(if y > 0.0 {
+                CardinalPrime::Up
+            } else {
+                CardinalPrime::Down
+            })
+        };
+
+        if new_direction != self.stick_direction {
+            self.stick_pressed = new_direction;
+        }
+        self.stick_direction = new_direction;
+    }
+
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed_buttons.contains(&button)
+    }
+
+    pub fn is_held(&self, button: GamepadButton) -> bool {
+        self.held_buttons.contains(&button)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_released(&self, button: GamepadButton) -> bool {
+        self.released_buttons.contains(&button)
+    }
+
+    /// The direction the stick was pushed into this frame.
+    pub fn stick_pressed(&self) -> Option<CardinalPrime> {
+        self.stick_pressed
+    }
+
+    /// The direction the stick is being held in.
+    pub fn stick_direction(&self) -> Option<CardinalPrime> {
+        self.stick_direction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{input_system, Binding, Input, InputAction, InputBindings};
+    use super::*;
+
+    /// Runs the gamepad half of one frame of input, the way `input_system` does.
+    fn next_frame(input: &mut Input, gamepad_source: &mut MockGamepadSource) {
+        input.clear_input();
+        input_system::poll_gamepad_events(input, gamepad_source);
+    }
+
+    #[test]
+    fn buttons_are_pressed_for_a_frame_and_held_until_released() {
+        let mut input = Input::new();
+        let mut gamepad_source = MockGamepadSource::default();
+
+        gamepad_source.push(GamepadEvent::ButtonPressed(GamepadButton::South));
+        next_frame(&mut input, &mut gamepad_source);
+        assert!(input.gamepad_input.is_pressed(GamepadButton::South));
+        assert!(input.gamepad_input.is_held(GamepadButton::South));
+
+        next_frame(&mut input, &mut gamepad_source);
+        assert!(input.gamepad_input.is_pressed(GamepadButton::South) == false);
+        assert!(input.gamepad_input.is_held(GamepadButton::South));
+
+        gamepad_source.push(GamepadEvent::ButtonReleased(GamepadButton::South));
+        next_frame(&mut input, &mut gamepad_source);
+        assert!(input.gamepad_input.is_held(GamepadButton::South) == false);
+        assert!(input.gamepad_input.is_released(GamepadButton::South));
+    }
+
+    #[test]
+    fn stick_inside_the_deadzone_has_no_direction() {
+        let mut input = Input::new();
+        let mut gamepad_source = MockGamepadSource::default();
+
+        gamepad_source.move_stick(0.3, -0.4);
+        next_frame(&mut input, &mut gamepad_source);
+        assert_eq!(input.gamepad_input.stick_pressed(), None);
+        assert_eq!(input.gamepad_input.stick_direction(), None);
+
+        // A smaller deadzone lets the same push through
+        input.gamepad_input.deadzone = 0.25;
+        next_frame(&mut input, &mut gamepad_source);
+        assert_eq!(input.gamepad_input.stick_pressed(), Some(CardinalPrime::Down));
+    }
+
+    #[test]
+    fn stick_maps_onto_its_strongest_cardinal() {
+        let mut input = Input::new();
+        let mut gamepad_source = MockGamepadSource::default();
+
+        let pushes = [
+            (0.8, 0.3, CardinalPrime::Right),
+            (-0.9, 0.2, CardinalPrime::Left),
+            (0.2, 0.7, CardinalPrime::Up),
+            (0.4, -0.9, CardinalPrime::Down),
+            // Ties go to the x axis
+            (-0.7, 0.7, CardinalPrime::Left),
+        ];
+
+        for (x, y, direction) in pushes.iter() {
+            gamepad_source.move_stick(*x, *y);
+            next_frame(&mut input, &mut gamepad_source);
+            assert_eq!(input.gamepad_input.stick_pressed(), Some(*direction));
+            assert_eq!(input.gamepad_input.stick_direction(), Some(*direction));
+
+            // Holding the stick there isn't a new press
+            next_frame(&mut input, &mut gamepad_source);
+            assert_eq!(input.gamepad_input.stick_pressed(), None);
+            assert_eq!(input.gamepad_input.stick_direction(), Some(*direction));
+
+            gamepad_source.move_stick(0.0, 0.0);
+            next_frame(&mut input, &mut gamepad_source);
+            assert_eq!(input.gamepad_input.stick_direction(), None);
+        }
+    }
+
+    #[test]
+    fn gamepad_bindings_follow_the_gamepad() {
+        let mut input = Input::new();
+        let mut gamepad_source = MockGamepadSource::default();
+        let input_bindings = InputBindings::default();
+
+        gamepad_source.push(GamepadEvent::ButtonPressed(GamepadButton::DPadLeft));
+        next_frame(&mut input, &mut gamepad_source);
+        assert!(input_bindings.is_pressed(InputAction::MoveLeft, &input));
+        assert!(input_bindings.is_pressed(InputAction::MoveRight, &input) == false);
+        assert!(Binding::Gamepad(GamepadButton::DPadLeft).is_held(&input));
+
+        gamepad_source.push(GamepadEvent::ButtonReleased(GamepadButton::DPadLeft));
+        gamepad_source.move_stick(0.0, -1.0);
+        next_frame(&mut input, &mut gamepad_source);
+        assert!(input_bindings.is_pressed(InputAction::MoveLeft, &input) == false);
+        assert!(Binding::Gamepad(GamepadButton::DPadLeft).is_held(&input) == false);
+        assert!(input_bindings.is_pressed(InputAction::MoveDown, &input));
+
+        next_frame(&mut input, &mut gamepad_source);
+        assert!(input_bindings.is_pressed(InputAction::MoveDown, &input) == false);
+        assert!(Binding::GamepadStick(CardinalPrime::Down).is_held(&input));
+
+        // Tapping a button presses it for one frame, even though it's already released
+        gamepad_source.tap(GamepadButton::West);
+        next_frame(&mut input, &mut gamepad_source);
+        assert!(input_bindings.is_pressed(InputAction::Undo, &input));
+        assert!(Binding::Gamepad(GamepadButton::West).is_held(&input) == false);
+    }
+}
@@ -1,26 +1,34 @@
-use super::{input::Input, renderer::RendererComponent, window::Window};
+use super::{
+    gamepad::{self, GamepadSource},
+    input::Input,
+    renderer::RendererComponent,
+    sound_player::SoundPlayer,
+    window::Window,
+};
 use anyhow::Error;
 
 pub struct HardwareInterface {
     pub input: Input,
+    pub gamepad_source: Box<dyn GamepadSource>,
     pub window: Window,
     pub renderer: RendererComponent,
-    // pub sound_player: SoundPlayer,
+    pub sound_player: SoundPlayer,
 }
 
 impl HardwareInterface {
     pub fn new(config: &super::game_config::Config) -> Result<Self, Error> {
         let window = Window::new(config.window_size)?;
         let renderer = RendererComponent::typed_new(&window.window)?;
-        // let sound_player = SoundPlayer::new();
+        let sound_player = SoundPlayer::new();
 
         info!("✔ Initialized Hardware Resources");
 
         Ok(Self {
             input: Input::new(),
+            gamepad_source: gamepad::default_gamepad_source(),
             window,
             renderer,
-            // sound_player,
+            sound_player,
         })
     }
 }
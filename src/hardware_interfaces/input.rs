@@ -1,4 +1,4 @@
-use super::Vec2;
+use super::{GamepadInput, Vec2};
 use winit::event::{ElementState, VirtualKeyCode};
 
 #[derive(Debug)]
@@ -7,6 +7,7 @@ pub struct Input {
     pub new_frame_size: Option<Vec2>,
     pub mouse_input: MouseInput,
     pub kb_input: KeyboardInput,
+    pub gamepad_input: GamepadInput,
 }
 
 impl Input {
@@ -16,6 +17,7 @@ impl Input {
             new_frame_size: None,
             mouse_input: MouseInput::default(),
             kb_input: KeyboardInput::default(),
+            gamepad_input: GamepadInput::default(),
         }
     }
 
@@ -24,6 +26,7 @@ impl Input {
         self.new_frame_size = None;
         self.mouse_input.clear();
         self.kb_input.clear();
+        self.gamepad_input.clear();
     }
 
     pub fn record_input(
@@ -34,6 +37,10 @@ impl Input {
     ) {
         match element_state {
             ElementState::Pressed => {
+                if self.kb_input.eaten_keys.contains(&code) {
+                    return;
+                }
+
                 if let None = last_frame_pressed.iter().position(|&pos| pos == code) {
                     if let None = self.kb_input.held_keys.iter().position(|&pos| pos == code) {
                         self.kb_input.pressed_keys.push(code);
@@ -43,6 +50,11 @@ impl Input {
             }
 
             ElementState::Released => {
+                if let Some(eaten_pos) = self.kb_input.eaten_keys.iter().position(|&item| item == code) {
+                    self.kb_input.eaten_keys.remove(eaten_pos);
+                    return;
+                }
+
                 if let Some(vk_pos) = self.kb_input.held_keys.iter().position(|&item| item == code) {
                     self.kb_input.held_keys.remove(vk_pos);
                     self.kb_input.released_keys.push(code);
@@ -52,14 +64,17 @@ impl Input {
     }
 }
 
+/// How many mouse buttons we track. Anything past these is ignored.
+pub const MOUSE_BUTTON_COUNT: usize = 5;
+
 #[derive(Debug, Default)]
 pub struct MouseInput {
     pub mouse_position_last_frame: Vec2,
     pub mouse_position: Vec2,
     pub mouse_vertical_scroll_delta: f32,
-    pub mouse_pressed: [bool; 5],
-    pub mouse_held: [bool; 5],
-    pub mouse_released: [bool; 5],
+    pub mouse_pressed: [bool; MOUSE_BUTTON_COUNT],
+    pub mouse_held: [bool; MOUSE_BUTTON_COUNT],
+    pub mouse_released: [bool; MOUSE_BUTTON_COUNT],
     pub mouse_input_taken: bool,
 }
 
@@ -85,19 +100,19 @@ impl MouseInput {
     #[allow(dead_code)]
     pub fn is_pressed(&self, mouse_button: MouseButton) -> bool {
         let index: usize = mouse_button.into();
-        self.mouse_pressed[index] && self.mouse_input_taken == false
+        self.mouse_pressed.get(index).cloned().unwrap_or(false) && self.mouse_input_taken == false
     }
 
     #[allow(dead_code)]
     pub fn is_held(&self, mouse_button: MouseButton) -> bool {
         let index: usize = mouse_button.into();
-        self.mouse_held[index] && self.mouse_input_taken == false
+        self.mouse_held.get(index).cloned().unwrap_or(false) && self.mouse_input_taken == false
     }
 
     #[allow(dead_code)]
     pub fn is_released(&self, mouse_button: MouseButton) -> bool {
         let index: usize = mouse_button.into();
-        self.mouse_released[index] || self.mouse_input_taken
+        self.mouse_released.get(index).cloned().unwrap_or(false) || self.mouse_input_taken
     }
 
     pub fn mouse_delta_position(&self) -> Vec2 {
@@ -105,7 +120,7 @@ impl MouseInput {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum MouseButton {
     Left,
     Right,
@@ -113,6 +128,17 @@ pub enum MouseButton {
     Generic(usize),
 }
 
+impl MouseButton {
+    /// Generic buttons are only the ones past Middle which we track. `Generic(0)`
+    /// would just be Left under another name.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            MouseButton::Generic(index) => *index > 2 && *index < MOUSE_BUTTON_COUNT,
+            _ => true,
+        }
+    }
+}
+
 impl From<MouseButton> for usize {
     fn from(w: MouseButton) -> usize {
         match w {
@@ -129,6 +155,8 @@ pub struct KeyboardInput {
     pub pressed_keys: Vec<VirtualKeyCode>,
     pub held_keys: Vec<VirtualKeyCode>,
     pub released_keys: Vec<VirtualKeyCode>,
+    /// Keys which were eaten, and won't count until they're released.
+    pub eaten_keys: Vec<VirtualKeyCode>,
 }
 
 impl KeyboardInput {
@@ -137,6 +165,17 @@ impl KeyboardInput {
         self.released_keys.clear();
     }
 
+    /// Swallows a Key until it's released, so it's neither pressed nor held in the
+    /// meantime. The ImGui editor eats the Key it just bound, so it doesn't also move a Player.
+    pub fn eat_key(&mut self, key: VirtualKeyCode) {
+        self.pressed_keys.retain(|&pressed_key| pressed_key != key);
+        self.held_keys.retain(|&held_key| held_key != key);
+
+        if self.eaten_keys.contains(&key) == false {
+            self.eaten_keys.push(key);
+        }
+    }
+
     #[allow(dead_code)]
     pub fn is_pressed(&self, target_keycode: VirtualKeyCode) -> bool {
         self.pressed_keys.contains(&target_keycode)
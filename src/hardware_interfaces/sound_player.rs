@@ -1,16 +1,272 @@
-// use rodio::{Device, Sink};
-
-// pub struct SoundPlayer {
-//     pub device: Device,
-//     pub sinks: Vec<Sink>,
-// }
-
-// impl SoundPlayer {
-//     pub fn new() -> Self {
-//         let device =  rodio::default_output_device().unwrap();
-//         Self {
-//             device,
-//             sinks: Vec::new(),
-//         }
-//     }
-// }
+use super::{game_config::VolumeBuses, SoundName};
+use rodio::{Device, Sink, Source};
+
+/// Plays sounds through a pool of Sinks, and music through its own Sinks so that
+/// tracks can crossfade. If there's no output device, we run as a null device:
+/// sounds are still decoded, but then dropped.
+pub struct SoundPlayer {
+    device: Option<Device>,
+    sinks: Vec<SoundSink>,
+    volumes: VolumeBuses,
+    music: Option<MusicTrack>,
+    fading_out: Vec<MusicTrack>,
+    /// A track which we couldn't decode. We don't try it again until it's reloaded.
+    failed_music: Option<SoundName>,
+}
+
+/// One Sink in the pool. A null device has no Sinks, so it keeps the pool the
+/// same way by counting down how long each sound would have played for.
+enum SoundSink {
+    Device(Sink),
+    /// Sounds which don't know their length finish straight away.
+    Null {
+        remaining: f32,
+    },
+}
+
+impl SoundSink {
+    fn empty(&self) -> bool {
+        match self {
+            SoundSink::Device(sink) => sink.empty(),
+            SoundSink::Null { remaining } => *remaining <= 0.0,
+        }
+    }
+}
+
+struct MusicTrack {
+    name: SoundName,
+    /// None on a null device.
+    sink: Option<Sink>,
+    volume: f32,
+    /// How far faded in we are, from 0.0 to 1.0.
+    fade: f32,
+    /// How much `fade` changes per second. Negative when fading out.
+    fade_speed: f32,
+}
+
+impl MusicTrack {
+    fn fade_speed(fade_time: f32) -> f32 {
+        if fade_time > 0.0 {
+            1.0 / fade_time
+        } else {
+            std::f32::INFINITY
+        }
+    }
+
+    fn apply_volume(&self, volumes: &VolumeBuses) {
+        if let Some(sink) = &self.sink {
+            sink.set_volume(self.volume * self.fade * volumes.music_volume());
+        }
+    }
+}
+
+impl SoundPlayer {
+    /// More than this many sounds at once, and we start dropping them.
+    pub const MAX_SINKS: usize = 16;
+
+    /// Setting `NULL_AUDIO_VAR` forces a null device, for running on CI.
+    pub const NULL_AUDIO_VAR: &str = "BIT_BOTS_NULL_AUDIO";
+
+    pub fn new() -> Self {
+        if std::env::var_os(Self::NULL_AUDIO_VAR).is_some() {
+            info!("{} is set, so sounds will not play.", Self::NULL_AUDIO_VAR);
+            return Self::null();
+        }
+
+        match rodio::default_output_device() {
+            Some(device) => Self {
+                device: Some(device),
+                ..Self::null()
+            },
+            None => {
+                error!("No audio output device! Sounds will not play.");
+                Self::null()
+            }
+        }
+    }
+
+    /// A SoundPlayer with no output device, for machines without audio.
+    pub fn null() -> Self {
+        Self {
+            device: None,
+            sinks: Vec::new(),
+            volumes: VolumeBuses::default(),
+            music: None,
+            fading_out: Vec::new(),
+            failed_music: None,
+        }
+    }
+
+    /// Plays a sound on the first free Sink, making a new one if they're all
+    /// busy. Returns false if the sound was dropped because the pool is full.
+    pub fn play<S>(&mut self, source: S) -> bool
+    where
+        S: Source + Send + 'static,
+        S::Item: rodio::Sample + Send,
+    {
+        let sink_index = match self.sinks.iter().position(|sink| sink.empty()) {
+            Some(sink_index) => sink_index,
+            None => {
+                if self.sinks.len() >= Self::MAX_SINKS {
+                    log_once::error_once!("Every sound sink is busy! Dropping sounds.");
+                    return false;
+                }
+
+                self.sinks.push(match &self.device {
+                    Some(device) => SoundSink::Device(Sink::new(device)),
+                    None => SoundSink::Null { remaining: 0.0 },
+                });
+                info!("Sound sink expanded -- we now have {} sinks.", self.sinks.len());
+                self.sinks.len() - 1
+            }
+        };
+
+        match &mut self.sinks[sink_index] {
+            SoundSink::Device(sink) => {
+                sink.set_volume(self.volumes.sfx_volume());
+                sink.append(source);
+            }
+            SoundSink::Null { remaining } => {
+                *remaining = source
+                    .total_duration()
+                    .map_or(0.0, |duration| duration.as_secs_f32());
+            }
+        }
+
+        true
+    }
+
+    pub fn current_music(&self) -> Option<&SoundName> {
+        self.music.as_ref().map(|music| &music.name)
+    }
+
+    /// Loops a music track, fading out whatever was playing before over the same time.
+    pub fn play_music<S>(&mut self, name: SoundName, source: S, volume: f32, fade_time: f32)
+    where
+        S: Source + Send + 'static,
+        S::Item: rodio::Sample + Send,
+    {
+        self.stop_music(fade_time);
+
+        let sink = self.device.as_ref().map(|device| {
+            let sink = Sink::new(device);
+            sink.set_volume(0.0);
+            sink.append(source.repeat_infinite());
+            sink
+        });
+
+        self.music = Some(MusicTrack {
+            name,
+            sink,
+            volume,
+            fade: 0.0,
+            fade_speed: MusicTrack::fade_speed(fade_time),
+        });
+    }
+
+    pub fn music_failed(&self, name: &SoundName) -> bool {
+        self.failed_music.as_ref() == Some(name)
+    }
+
+    pub fn set_music_failed(&mut self, name: SoundName) {
+        self.failed_music = Some(name);
+    }
+
+    /// Lets a track which failed be tried again, and restarts the current track if
+    /// its file changed. Call this with whatever `ResourcesDatabase::reload_sounds` loaded.
+    pub fn sounds_reloaded(&mut self, reloaded: &[SoundName]) {
+        if let Some(failed_music) = &self.failed_music {
+            if reloaded.contains(failed_music) {
+                self.failed_music = None;
+            }
+        }
+
+        // Once it's stopped, `update_music` starts it again from the new file
+        let music_reloaded = self
+            .music
+            .as_ref()
+            .map_or(false, |music| reloaded.contains(&music.name));
+        if music_reloaded {
+            self.stop_music(0.0);
+        }
+    }
+
+    pub fn stop_music(&mut self, fade_time: f32) {
+        if let Some(mut music) = self.music.take() {
+            music.fade_speed = -MusicTrack::fade_speed(fade_time);
+            self.fading_out.push(music);
+        }
+    }
+
+    pub fn set_music_volume(&mut self, volume: f32) {
+        if let Some(music) = &mut self.music {
+            music.volume = volume;
+        }
+    }
+
+    /// Moves fades along and applies the volume buses. Call this once a frame.
+    pub fn update(&mut self, delta_time: f32, volumes: &VolumeBuses) {
+        if &self.volumes != volumes {
+            self.volumes = *volumes;
+
+            for sink in self.sinks.iter() {
+                if let SoundSink::Device(sink) = sink {
+                    sink.set_volume(self.volumes.sfx_volume());
+                }
+            }
+        }
+
+        for sink in self.sinks.iter_mut() {
+            if let SoundSink::Null { remaining } = sink {
+                *remaining -= delta_time;
+            }
+        }
+
+        if let Some(music) = &mut self.music {
+            music.fade = (music.fade + music.fade_speed * delta_time).min(1.0);
+            music.apply_volume(&self.volumes);
+        }
+
+        let volumes = &self.volumes;
+        for music in self.fading_out.iter_mut() {
+            music.fade = (music.fade + music.fade_speed * delta_time).max(0.0);
+            music.apply_volume(volumes);
+        }
+        // Dropping a Sink stops it
+        self.fading_out.retain(|music| music.fade > 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    /// A second of silence, which knows how long it is.
+    fn one_second() -> SamplesBuffer<i16> {
+        SamplesBuffer::new(1, 44_100, vec![0; 44_100])
+    }
+
+    #[test]
+    fn null_device_drops_sounds_once_every_sink_is_busy() {
+        let mut sound_player = SoundPlayer::null();
+        let volumes = VolumeBuses::default();
+
+        for _ in 0..SoundPlayer::MAX_SINKS {
+            assert!(sound_player.play(one_second()));
+        }
+        assert!(sound_player.play(one_second()) == false);
+
+        // Half way through, every sink is still busy...
+        sound_player.update(0.5, &volumes);
+        assert!(sound_player.play(one_second()) == false);
+
+        // ...but once they've finished, they're free again
+        sound_player.update(0.6, &volumes);
+        for _ in 0..SoundPlayer::MAX_SINKS {
+            assert!(sound_player.play(one_second()));
+        }
+        assert!(sound_player.play(one_second()) == false);
+        assert_eq!(sound_player.sinks.len(), SoundPlayer::MAX_SINKS);
+    }
+}
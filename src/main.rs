@@ -11,6 +11,7 @@ extern crate anyhow;
 
 mod action_map;
 mod clockwork;
+mod command_buffer;
 mod components;
 mod components_singleton;
 mod ecs;
@@ -20,13 +21,15 @@ mod physics;
 mod resources;
 mod scene;
 mod scene_graph;
+mod scheduler;
 mod serialization;
 mod systems;
 mod tick_structs;
 mod utilities;
 
-pub use action_map::ActionMap;
+pub use action_map::{ActionMap, MovementRepeat};
 pub use clockwork::*;
+pub use command_buffer::*;
 pub use components::*;
 pub use components_singleton::*;
 pub use ecs::*;
@@ -36,6 +39,7 @@ pub use physics::*;
 pub use resources::*;
 pub use scene::*;
 pub use scene_graph::*;
+pub use scheduler::*;
 pub use serialization::*;
 pub use systems::*;
 pub use tick_structs::*;
@@ -44,8 +48,43 @@ pub use utilities::*;
 fn main() {
     pretty_env_logger::init();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--recover") {
+        serialization::set_recovery_mode(true);
+    }
+
+    // Headless modes, which never open a window
+    let headless_result = solver_system::run_from_args(&args)
+        .or_else(|| replay_system::run_from_args(&args))
+        .or_else(|| migrations::run_from_args(&args))
+        .or_else(|| serialization_util::cooked::run_from_args(&args));
+    if let Some(result) = headless_result {
+        match result {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                error!("Headless Error: {}", e);
+                for this_cause in e.chain() {
+                    error!("{}", this_cause);
+                }
+                std::process::exit(1);
+            }
+        }
+
+        return;
+    }
+
     let mut clockwork = match clockwork::Clockwork::new() {
-        Ok(clockwork) => clockwork,
+        Ok(mut clockwork) => {
+            if let Some(path) = replay_system::record_path_from_args(&args) {
+                clockwork.replay_recorder = Some(replay_system::ReplayRecorder::new(
+                    path,
+                    scene_system::current_scene_name(),
+                ));
+            }
+
+            clockwork
+        }
         Err(e) => {
             error!("Error on Startup: {}", e);
             for this_cause in e.chain() {
@@ -2,12 +2,16 @@ use super::*;
 
 pub mod fonts;
 pub mod game_config;
+mod input_bindings;
+mod interaction_rules;
 mod prefab;
 mod resources_database;
 mod sound_resource;
 pub mod sprite_resources;
 pub mod tile_resources;
 
+pub use input_bindings::{Binding, InputAction, InputBindings};
+pub use interaction_rules::{Interaction, InteractionRule, InteractionRules};
 pub use prefab::*;
 pub use resources_database::ResourcesDatabase;
-pub use sound_resource::SoundResource;
+pub use sound_resource::{SoundFile, SoundName, SoundResource, SOUND_DIRECTORY, SOUND_EXTENSIONS};
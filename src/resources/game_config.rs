@@ -1,9 +1,13 @@
 use super::Vec2;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub window_size: Vec2,
     pub imgui_pixel_size: f32,
+    pub volume: VolumeBuses,
+    /// How many old versions of each Scene and Prefab file to keep. See `safe_files`.
+    pub backup_count: usize,
 }
 
 impl Default for Config {
@@ -11,6 +15,37 @@ impl Default for Config {
         Self {
             window_size: Vec2::new(1280.0, 720.0),
             imgui_pixel_size: 20.0,
+            volume: VolumeBuses::default(),
+            backup_count: 0,
+        }
+    }
+}
+
+/// Every sound goes through `master`, and then through either `music` or `sfx`.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolumeBuses {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl VolumeBuses {
+    pub fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.master * self.sfx
+    }
+}
+
+impl Default for VolumeBuses {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.8,
+            sfx: 1.0,
         }
     }
 }
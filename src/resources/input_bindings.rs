@@ -0,0 +1,174 @@
+use super::{cardinals::CardinalPrime, GamepadButton, Input, MouseButton};
+use std::collections::BTreeMap;
+use winit::event::VirtualKeyCode as VK;
+
+/// Everything the player can ask for. ActionMap is built out of these.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum_macros::EnumIter,
+    strum_macros::Display,
+)]
+pub enum InputAction {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    SwitchPlayerLeft,
+    SwitchPlayerRight,
+    Undo,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(VK),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    /// The left stick, pushed past its deadzone in a direction.
+    GamepadStick(CardinalPrime),
+}
+
+impl Binding {
+    pub fn is_pressed(&self, input: &Input) -> bool {
+        match self {
+            Binding::Key(key) => input.kb_input.is_pressed(*key),
+            Binding::Mouse(button) => input.mouse_input.is_pressed(*button),
+            Binding::Gamepad(button) => input.gamepad_input.is_pressed(*button),
+            Binding::GamepadStick(direction) => input.gamepad_input.stick_pressed() == Some(*direction),
+        }
+    }
+
+    pub fn is_held(&self, input: &Input) -> bool {
+        match self {
+            Binding::Key(key) => input.kb_input.is_held(*key),
+            Binding::Mouse(button) => input.mouse_input.is_held(*button),
+            Binding::Gamepad(button) => input.gamepad_input.is_held(*button),
+            Binding::GamepadStick(direction) => input.gamepad_input.stick_direction() == Some(*direction),
+        }
+    }
+}
+
+/// Which keys and mouse buttons trigger each InputAction. This lives next to the
+/// Config in `dev/input_bindings.yaml`, and can be rebound live through ImGui.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    pub bindings: BTreeMap<InputAction, Vec<Binding>>,
+    /// If true, holding a direction keeps moving in it.
+    pub hold_to_repeat: bool,
+    /// Seconds a direction has to be held before it starts repeating.
+    pub repeat_delay: f32,
+    /// Seconds between each repeated move.
+    pub repeat_interval: f32,
+    /// The InputAction which the ImGui editor is waiting on a key for.
+    #[serde(skip)]
+    pub rebinding: Option<InputAction>,
+}
+
+impl InputBindings {
+    pub fn bindings_for(&self, action: InputAction) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    pub fn is_pressed(&self, action: InputAction, input: &Input) -> bool {
+        self.bindings_for(action)
+            .iter()
+            .any(|binding| binding.is_pressed(input))
+    }
+
+    /// Drops every Mouse Binding to a button we don't track, like `Generic(0)`.
+    pub fn remove_invalid_bindings(&mut self) {
+        for (action, bindings) in self.bindings.iter_mut() {
+            bindings.retain(|binding| match binding {
+                Binding::Mouse(button) if button.is_valid() == false => {
+                    error!(
+                        "{} was bound to {:?}, which isn't a mouse button we track. We've dropped it.",
+                        action, button
+                    );
+                    false
+                }
+                _ => true,
+            });
+        }
+    }
+
+    /// Adds a Binding to an InputAction, unless it's already there.
+    pub fn bind(&mut self, action: InputAction, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if bindings.contains(&binding) == false {
+            bindings.push(binding);
+        }
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let mut bindings = BTreeMap::new();
+        bindings.insert(
+            InputAction::MoveLeft,
+            vec![
+                Binding::Key(VK::Left),
+                Binding::Key(VK::A),
+                Binding::Gamepad(GamepadButton::DPadLeft),
+                Binding::GamepadStick(CardinalPrime::Left),
+            ],
+        );
+        bindings.insert(
+            InputAction::MoveRight,
+            vec![
+                Binding::Key(VK::Right),
+                Binding::Key(VK::D),
+                Binding::Gamepad(GamepadButton::DPadRight),
+                Binding::GamepadStick(CardinalPrime::Right),
+            ],
+        );
+        bindings.insert(
+            InputAction::MoveUp,
+            vec![
+                Binding::Key(VK::Up),
+                Binding::Key(VK::W),
+                Binding::Gamepad(GamepadButton::DPadUp),
+                Binding::GamepadStick(CardinalPrime::Up),
+            ],
+        );
+        bindings.insert(
+            InputAction::MoveDown,
+            vec![
+                Binding::Key(VK::Down),
+                Binding::Key(VK::S),
+                Binding::Gamepad(GamepadButton::DPadDown),
+                Binding::GamepadStick(CardinalPrime::Down),
+            ],
+        );
+        bindings.insert(
+            InputAction::SwitchPlayerLeft,
+            vec![Binding::Key(VK::Q), Binding::Gamepad(GamepadButton::LeftBumper)],
+        );
+        bindings.insert(
+            InputAction::SwitchPlayerRight,
+            vec![Binding::Key(VK::E), Binding::Gamepad(GamepadButton::RightBumper)],
+        );
+        bindings.insert(
+            InputAction::Undo,
+            vec![Binding::Key(VK::Z), Binding::Gamepad(GamepadButton::West)],
+        );
+
+        Self {
+            bindings,
+            hold_to_repeat: true,
+            repeat_delay: 0.3,
+            repeat_interval: 0.15,
+            rebinding: None,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::GridType;
+
+/// What happens when something moves into a cell which already has
+/// something else in it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Interaction {
+    /// Nothing happens. The mover shares the cell with the occupant.
+    Pass,
+    /// The mover can't enter the cell.
+    Block,
+    /// The occupant is moved one cell further in the same direction. If it
+    /// can't move, neither can the mover.
+    Push,
+    /// The occupant is moved into the cell the mover just left.
+    Swap,
+    /// The occupant is taken off the Grid and hidden once the mover arrives.
+    Destroy,
+    /// The occupant's SceneSwitcher is triggered once the mover arrives.
+    SwitchScene,
+    /// A named GridEvent is raised once the mover arrives.
+    TriggerEvent(String),
+}
+
+impl Default for Interaction {
+    fn default() -> Self {
+        Self::Pass
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractionRule {
+    /// If None, this rule applies to every mover.
+    pub mover: Option<GridType>,
+    pub occupant: GridType,
+    pub interaction: Interaction,
+}
+
+impl InteractionRule {
+    pub fn new(mover: Option<GridType>, occupant: GridType, interaction: Interaction) -> Self {
+        Self {
+            mover,
+            occupant,
+            interaction,
+        }
+    }
+}
+
+/// The table of Interactions for a Scene. Rules are checked in order, and the
+/// first one which matches wins, so specific rules should come before general ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InteractionRules {
+    pub rules: Vec<InteractionRule>,
+}
+
+impl InteractionRules {
+    pub fn interaction(&self, mover: GridType, occupant: GridType) -> &Interaction {
+        const PASS: &Interaction = &Interaction::Pass;
+
+        self.rules
+            .iter()
+            .find(|rule| rule.occupant == occupant && rule.mover.map_or(true, |m| m == mover))
+            .map_or(PASS, |rule| &rule.interaction)
+    }
+}
+
+impl Default for InteractionRules {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                InteractionRule::new(Some(GridType::Player), GridType::Flag, Interaction::SwitchScene),
+                InteractionRule::new(None, GridType::Pushable, Interaction::Push),
+                InteractionRule::new(None, GridType::Blockable, Interaction::Block),
+                InteractionRule::new(None, GridType::Player, Interaction::Block),
+            ],
+        }
+    }
+}
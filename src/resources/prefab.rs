@@ -1,10 +1,16 @@
-use super::SerializedEntity;
+use super::{PrefabMarker, SerializedComponent, SerializedEntity};
 use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Where the Key in the HashMap is the same as the MainID in the Prefab.
 pub type PrefabMap = HashMap<Uuid, Prefab>;
 
+/// A Prefab is a RootEntity and the members of its Scene Graph.
+///
+/// A Prefab is a variant when its RootEntity has a PrefabMarker, which names the
+/// Prefab it's based on. Each member of a variant only saves its overrides of the
+/// base's member, and members it doesn't override aren't saved at all. Bases can be
+/// variants in turn -- see `prefab_system::prefab_chain`.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Prefab {
     root_id: Uuid,
@@ -85,6 +91,31 @@ impl Prefab {
         self.root_id
     }
 
+    /// The Prefab this is a variant of, if it is one.
+    pub fn base_id(&self) -> Option<Uuid> {
+        self.root_entity()
+            .prefab_marker
+            .as_ref()
+            .map(|prefab_marker| prefab_marker.inner.main_id())
+    }
+
+    /// The member as it's saved in this Prefab. Variants don't save the members they
+    /// inherit without changes, so for those, this is a member with no overrides.
+    pub fn member_overrides(&self, member_id: &Uuid) -> Option<SerializedEntity> {
+        if let Some(member) = self.members.get(member_id) {
+            return Some(member.clone());
+        }
+
+        self.base_id().map(|base_id| SerializedEntity {
+            id: *member_id,
+            prefab_marker: Some(SerializedComponent {
+                inner: PrefabMarker::new(base_id, *member_id),
+                active: true,
+            }),
+            ..Default::default()
+        })
+    }
+
     pub fn invalidate(&mut self) {
         self.valid = false;
     }
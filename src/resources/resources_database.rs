@@ -4,22 +4,23 @@ use super::{
     renderer_system, serialization_util,
     sprite_resources::{SpriteData, SpriteInGameData, SpriteName, TextureInformation},
     tile_resources::{TileSet, TileSetName, TileSetSerialized},
-    Prefab, PrefabMap, RendererComponent, SoundResource, Vec2,
+    InputBindings, Prefab, PrefabMap, RendererComponent, SoundFile, SoundName, Vec2, SOUND_DIRECTORY,
+    SOUND_EXTENSIONS,
 };
 use anyhow::Error;
 use glyph_brush::GlyphBrushBuilder;
 use std::collections::HashMap;
 use std::fs;
-use std::io::Cursor;
 use std::str::FromStr;
 use strum::IntoEnumIterator;
 
 pub struct ResourcesDatabase {
     pub sprites: HashMap<SpriteName, SpriteData>,
     pub tilesets: HashMap<TileSetName, TileSet>,
-    pub sounds: HashMap<SoundResource, Cursor<&'static [u8]>>,
+    pub sounds: HashMap<SoundName, SoundFile>,
     pub fonts: HashMap<FontName, FontData>,
     pub config: Config,
+    pub input_bindings: InputBindings,
     prefabs: PrefabMap,
 }
 
@@ -32,6 +33,7 @@ impl ResourcesDatabase {
             fonts: HashMap::new(),
             prefabs: HashMap::new(),
             config: serialization_util::game_config::load_config().unwrap_or_default(),
+            input_bindings: serialization_util::input_bindings::load_input_bindings().unwrap_or_default(),
         }
     }
 
@@ -153,15 +155,66 @@ impl ResourcesDatabase {
         Ok(())
     }
 
+    /// Finds every sound in `assets/audio`, including subdirectories.
     pub fn initialize_sounds(&mut self) -> Result<(), Error> {
-        for this_sound in SoundResource::iter() {
-            let sound_file = this_sound.get_sound_file();
-            self.sounds.insert(this_sound, Cursor::new(sound_file));
-        }
+        self.sounds.clear();
+        self.reload_sounds()?;
 
         Ok(())
     }
 
+    /// Loads any sound which is new or has changed on disk since we last loaded it,
+    /// and forgets any which were deleted. Returns the names of the sounds (re)loaded.
+    pub fn reload_sounds(&mut self) -> Result<Vec<SoundName>, Error> {
+        let mut found = std::collections::HashSet::new();
+        let mut loaded = vec![];
+
+        for extension in SOUND_EXTENSIONS.iter() {
+            for entry in glob::glob(&format!("{}/**/*.{}", SOUND_DIRECTORY, extension))? {
+                let path = entry?;
+                let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                    Some(stem) => SoundName::new(stem),
+                    None => continue,
+                };
+
+                let modified = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+                if let Some(sound_file) = self.sounds.get(&name) {
+                    if sound_file.path != path && found.contains(&name) {
+                        log_once::error_once!(
+                            "Sounds {} and {} share the name {}!",
+                            sound_file.path.display(),
+                            path.display(),
+                            name
+                        );
+                        continue;
+                    }
+
+                    if sound_file.path == path && sound_file.modified == modified && modified.is_some() {
+                        found.insert(name);
+                        continue;
+                    }
+                }
+
+                let data = fs::read(&path)?;
+                info!("Loaded Sound {}", name);
+                self.sounds.insert(
+                    name.clone(),
+                    SoundFile {
+                        path,
+                        data: data.into(),
+                        modified,
+                    },
+                );
+                found.insert(name.clone());
+                loaded.push(name);
+            }
+        }
+
+        self.sounds.retain(|name, _| found.contains(name));
+
+        Ok(loaded)
+    }
+
     pub fn initialize_fonts(&mut self) -> Result<(), Error> {
         for font in FontName::iter() {
             let font_file = fs::read(&format!("assets/fonts/{}.ttf", font.to_string()))?;
@@ -1,5 +1,70 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
 use strum_macros::{EnumCount, EnumIter, EnumString};
 
+pub const SOUND_DIRECTORY: &str = "assets/audio";
+pub const SOUND_EXTENSIONS: [&str; 3] = ["ogg", "wav", "flac"];
+
+/// The name of a sound in `assets/audio`, which is its file stem in snake case.
+/// `back.ogg`, `Back` and `back` all name the same sound, so older Scenes which
+/// used SoundResource variants still load.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct SoundName(String);
+
+impl SoundName {
+    pub fn new(name: &str) -> Self {
+        let mut snake_case = String::with_capacity(name.len());
+
+        for (i, c) in name.chars().enumerate() {
+            if c.is_uppercase() {
+                if i != 0 && snake_case.ends_with('_') == false {
+                    snake_case.push('_');
+                }
+                snake_case.extend(c.to_lowercase());
+            } else {
+                snake_case.push(c);
+            }
+        }
+
+        Self(snake_case)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SoundName {
+    fn from(name: String) -> Self {
+        Self::new(&name)
+    }
+}
+
+impl From<SoundName> for String {
+    fn from(sound_name: SoundName) -> Self {
+        sound_name.0
+    }
+}
+
+impl std::fmt::Display for SoundName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A sound loaded from disk. We keep when it was last modified, so we can
+/// reload it when it changes.
+#[derive(Debug, Clone)]
+pub struct SoundFile {
+    pub path: PathBuf,
+    pub data: Arc<[u8]>,
+    pub modified: Option<SystemTime>,
+}
+
+/// Typed names for sounds which code wants to play directly. These are only
+/// aliases -- sounds don't need a variant here to be used in Scenes.
 #[derive(
     Debug,
     PartialEq,
@@ -14,16 +79,14 @@ use strum_macros::{EnumCount, EnumIter, EnumString};
     Deserialize,
     typename::TypeName,
 )]
+#[allow(dead_code)]
 pub enum SoundResource {
     Back,
     Cursor,
 }
 
-impl SoundResource {
-    pub(super) fn get_sound_file(&self) -> &'static [u8] {
-        match self {
-            Self::Back => include_bytes!("../../assets/audio/old_files/back.ogg"),
-            Self::Cursor => include_bytes!("../../assets/audio/old_files/cursor.ogg"),
-        }
+impl From<SoundResource> for SoundName {
+    fn from(sound_resource: SoundResource) -> Self {
+        SoundName::new(&format!("{:?}", sound_resource))
     }
 }
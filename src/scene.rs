@@ -1,8 +1,10 @@
 pub const ENTITY_SUBPATH: &str = "entities_data.yaml";
+pub const ENTITY_DIRECTORY_SUBPATH: &str = "entities";
 pub const SCENE_DIRECTORY: &str = "assets/serialized_data/scenes";
 pub const PREFAB_DIRECTORY: &str = "assets/serialized_data/prefabs";
 pub const SINGLETONS_SUBPATH: &str = "singleton_data.yaml";
 pub const DEFAULT_SINGLETONS_SUBPATH: &str = "default_singleton_data.yaml";
+pub const INTERACTION_RULES_SUBPATH: &str = "interaction_rules.yaml";
 pub const TILEMAP_SUBPATH: &str = "tilemap";
 
 #[derive(Debug, Clone)]
@@ -57,6 +59,12 @@ impl Scene {
         }
     }
 
+    /// Where a Scene keeps its entities, one file each. Scenes saved before then
+    /// have them all in `entity_path` instead. Prefabs always do.
+    pub fn entity_directory(&self) -> String {
+        format!("{}/{}/{}", SCENE_DIRECTORY, &self.name, ENTITY_DIRECTORY_SUBPATH)
+    }
+
     pub fn singleton_path(&self) -> String {
         if self.is_prefab {
             format!("{}/{}", PREFAB_DIRECTORY, DEFAULT_SINGLETONS_SUBPATH)
@@ -65,6 +73,14 @@ impl Scene {
         }
     }
 
+    pub fn interaction_rules_path(&self) -> String {
+        if self.is_prefab {
+            format!("{}/{}", PREFAB_DIRECTORY, INTERACTION_RULES_SUBPATH)
+        } else {
+            format!("{}/{}/{}", SCENE_DIRECTORY, &self.name, INTERACTION_RULES_SUBPATH)
+        }
+    }
+
     pub fn tilemap_path(&self, tilemap_path_end: &str) -> String {
         if self.is_prefab {
             format!("{}/{}/{}", PREFAB_DIRECTORY, TILEMAP_SUBPATH, tilemap_path_end)
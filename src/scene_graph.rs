@@ -28,6 +28,44 @@ pub fn clear_root() {
     }
 }
 
+/// The Entity and every Entity under it in the scene graph, with parents before their children.
+pub fn entity_tree(entity: Entity, nodes: &ComponentList<GraphNode>) -> Vec<Entity> {
+    let mut tree = vec![entity];
+
+    let mut next = 0;
+    while let Some(parent) = tree.get(next).copied() {
+        let children: Vec<Entity> = nodes
+            .get(&parent)
+            .and_then(|node| node.inner().children.as_ref())
+            .map(|children| children.iter().filter_map(|child| child.target).collect())
+            .unwrap_or_default();
+
+        for child in children {
+            // A broken graph could loop back on itself
+            if tree.contains(&child) == false {
+                tree.push(child);
+            }
+        }
+
+        next += 1;
+    }
+
+    tree
+}
+
+/// Takes the Entity out of its parent's children, whether that's a GraphNode or the root.
+/// Not every child has a Transform pointing at its parent, so we check every node.
+pub fn remove_from_parent(entity: Entity, nodes: &mut ComponentList<GraphNode>) {
+    let mut root_nodes = ROOT_NODES.lock().unwrap();
+
+    let every_node = std::iter::once(&mut *root_nodes).chain(nodes.iter_mut().map(|node| node.inner_mut()));
+    for node in every_node {
+        if let Some(children) = &mut node.children {
+            children.retain(|child| child.target != Some(entity));
+        }
+    }
+}
+
 pub fn walk_graph(transforms: &mut ComponentList<Transform>, nodes: &ComponentList<GraphNode>) {
     let root_nodes = ROOT_NODES.lock().unwrap();
 
@@ -144,24 +182,27 @@ fn walk_node_inspect(
     }
 
     if show_children {
-        // We're forced to break Rust borrowing rules here again, because we're a bad bitch.
-        let graph_nodes: *const ComponentList<GraphNode> = &component_database.graph_nodes;
-
-        if let Some(this_node) = unsafe { &*graph_nodes }.get(entity) {
-            if let Some(children) = &this_node.inner().children {
-                for child in children {
-                    if let Some(target) = &child.target {
-                        walk_node_inspect(
-                            target,
-                            component_database,
-                            singleton_database,
-                            resources,
-                            depth + 1,
-                            f,
-                        );
-                    }
-                }
+        // Walking a child needs the whole ComponentDatabase, so we look our
+        // children up again for each one rather than holding onto them.
+        let mut child_index = 0;
+        while let Some(child_target) = component_database
+            .graph_nodes
+            .get(entity)
+            .and_then(|node| node.inner().children.as_ref()?.get(child_index))
+            .map(|child| child.target)
+        {
+            if let Some(target) = &child_target {
+                walk_node_inspect(
+                    target,
+                    component_database,
+                    singleton_database,
+                    resources,
+                    depth + 1,
+                    f,
+                );
             }
+
+            child_index += 1;
         }
     }
 }
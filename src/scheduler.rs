@@ -0,0 +1,556 @@
+use super::{
+    systems::{grid_system::Grid, history_system::MoveHistory, level_system::LevelState, *},
+    ActionMap, CommandBuffer, Ecs, PrefabMap, ResourcesDatabase, SceneMode, SoundPlayer, UiHandler, Vec2,
+};
+use anyhow::Error;
+use imgui::*;
+use std::time::{Duration, Instant};
+
+/// When a system runs. Fixed systems run on every fixed tick, which can be zero
+/// or several times a frame. Frame systems run once a frame, whatever the tick rate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Tick {
+    Fixed,
+    Frame,
+}
+
+/// Everything a system can work with. Systems share this, so anything one
+/// system needs to tell a later one goes into `tick_state`. Systems which make
+/// or destroy Entities push into `commands`, which are applied once the Tick is over.
+pub struct SystemParameters<'a> {
+    pub ecs: &'a mut Ecs,
+    pub grid: &'a mut Grid,
+    pub move_history: &'a mut MoveHistory,
+    pub level_state: &'a mut LevelState,
+    pub actions: &'a ActionMap,
+    pub delta_time: f32,
+    pub tick_count: u64,
+    /// None when we're headless, such as when playing back a Replay.
+    pub runtime: Option<RuntimeParameters<'a>>,
+    pub tick_state: TickState,
+    pub commands: CommandBuffer,
+}
+
+pub struct RuntimeParameters<'a> {
+    pub resources: &'a mut ResourcesDatabase,
+    pub sound_player: &'a mut SoundPlayer,
+}
+
+/// Written by systems for the systems after them. This is reset every time the
+/// Scheduler runs.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct TickState {
+    /// Nothing should move on the board for the rest of this tick.
+    pub board_frozen: bool,
+    pub turn_taken: bool,
+}
+
+pub struct SystemDescriptor {
+    pub name: &'static str,
+    pub tick: Tick,
+    /// The SceneModes this system runs in.
+    pub modes: &'static [SceneMode],
+    /// Systems with the same Tick which have to run before this one.
+    pub after: &'static [&'static str],
+    /// Headless systems only need the Ecs and the board, so they also run
+    /// without a window. Every other system needs `runtime`.
+    pub headless: bool,
+    pub run: fn(&mut SystemParameters<'_>) -> Result<(), Error>,
+}
+
+const PLAYING: &[SceneMode] = &[SceneMode::Playing];
+const ALL_MODES: &[SceneMode] = &[SceneMode::Draft, SceneMode::Playing, SceneMode::Paused];
+
+/// Every system in the game. The Scheduler works out the order they run in
+/// from `after`, and otherwise keeps them in the order they're listed here.
+pub const SYSTEMS: &[SystemDescriptor] = &[
+    SystemDescriptor {
+        name: "undo",
+        tick: Tick::Fixed,
+        modes: PLAYING,
+        after: &[],
+        headless: true,
+        run: undo,
+    },
+    SystemDescriptor {
+        name: "player",
+        tick: Tick::Fixed,
+        modes: PLAYING,
+        after: &["undo"],
+        headless: true,
+        run: player,
+    },
+    SystemDescriptor {
+        name: "grid",
+        tick: Tick::Fixed,
+        modes: PLAYING,
+        after: &["player"],
+        headless: true,
+        run: grid,
+    },
+    SystemDescriptor {
+        name: "level",
+        tick: Tick::Fixed,
+        modes: PLAYING,
+        after: &["grid"],
+        headless: true,
+        run: level,
+    },
+    SystemDescriptor {
+        name: "follows",
+        tick: Tick::Fixed,
+        modes: PLAYING,
+        after: &["grid"],
+        headless: true,
+        run: follows,
+    },
+    SystemDescriptor {
+        name: "conversant_npcs",
+        tick: Tick::Fixed,
+        modes: PLAYING,
+        after: &["follows"],
+        headless: false,
+        run: conversant_npcs,
+    },
+    SystemDescriptor {
+        name: "sprites",
+        tick: Tick::Fixed,
+        modes: PLAYING,
+        after: &["level"],
+        headless: false,
+        run: sprites,
+    },
+    SystemDescriptor {
+        name: "cross_cutting",
+        tick: Tick::Fixed,
+        modes: PLAYING,
+        after: &["sprites"],
+        headless: false,
+        run: cross_cutting,
+    },
+    SystemDescriptor {
+        name: "sound_reload",
+        tick: Tick::Frame,
+        modes: ALL_MODES,
+        after: &[],
+        headless: false,
+        run: sound_reload,
+    },
+    SystemDescriptor {
+        name: "sounds",
+        tick: Tick::Frame,
+        modes: ALL_MODES,
+        after: &["sound_reload"],
+        headless: false,
+        run: sounds,
+    },
+    SystemDescriptor {
+        name: "music",
+        tick: Tick::Frame,
+        modes: ALL_MODES,
+        after: &["sound_reload"],
+        headless: false,
+        run: music,
+    },
+];
+
+/// Runs systems in order, skipping any which are toggled off or whose run
+/// criteria don't match, and times each one.
+pub struct Scheduler {
+    systems: Vec<ScheduledSystem>,
+}
+
+struct ScheduledSystem {
+    descriptor: &'static SystemDescriptor,
+    enabled: bool,
+    timing: SystemTiming,
+}
+
+#[derive(Debug, Default)]
+struct SystemTiming {
+    last: Duration,
+    /// An exponential moving average, in milliseconds.
+    average_ms: f32,
+}
+
+impl SystemTiming {
+    const SMOOTHING: f32 = 0.05;
+
+    fn record(&mut self, duration: Duration) {
+        self.last = duration;
+
+        let ms = duration.as_secs_f32() * 1000.0;
+        self.average_ms += (ms - self.average_ms) * Self::SMOOTHING;
+    }
+}
+
+impl Scheduler {
+    pub fn new(descriptors: &'static [SystemDescriptor]) -> Result<Self, Error> {
+        for (i, descriptor) in descriptors.iter().enumerate() {
+            if descriptors[..i].iter().any(|d| d.name == descriptor.name) {
+                bail!("There are two systems named {}!", descriptor.name);
+            }
+
+            for before in descriptor.after {
+                match descriptors.iter().find(|d| d.name == *before) {
+                    Some(before_descriptor) => {
+                        if before_descriptor.tick != descriptor.tick {
+                            bail!(
+                                "{} runs after {}, but they don't run on the same Tick!",
+                                descriptor.name,
+                                before
+                            );
+                        }
+                    }
+                    None => bail!(
+                        "{} runs after {}, but there's no system by that name!",
+                        descriptor.name,
+                        before
+                    ),
+                }
+            }
+        }
+
+        // We take the first system whose dependencies have all run, so systems
+        // without constraints between them stay in the order they're listed.
+        let mut remaining: Vec<&'static SystemDescriptor> = descriptors.iter().collect();
+        let mut systems: Vec<ScheduledSystem> = Vec::with_capacity(descriptors.len());
+
+        while remaining.is_empty() == false {
+            let next = remaining.iter().position(|descriptor| {
+                descriptor
+                    .after
+                    .iter()
+                    .all(|before| systems.iter().any(|s| s.descriptor.name == *before))
+            });
+
+            match next {
+                Some(next) => systems.push(ScheduledSystem {
+                    descriptor: remaining.remove(next),
+                    enabled: true,
+                    timing: SystemTiming::default(),
+                }),
+                None => {
+                    let names: Vec<&str> = remaining.iter().map(|d| d.name).collect();
+                    bail!("These systems all wait on each other: {}", names.join(", "));
+                }
+            }
+        }
+
+        Ok(Self { systems })
+    }
+
+    /// Runs every system for this Tick which runs in this SceneMode.
+    pub fn run(
+        &mut self,
+        tick: Tick,
+        scene_mode: SceneMode,
+        system_parameters: &mut SystemParameters<'_>,
+    ) -> Result<(), Error> {
+        system_parameters.tick_state = TickState::default();
+
+        for system in self.systems.iter_mut() {
+            let descriptor = system.descriptor;
+            if descriptor.tick != tick
+                || system.enabled == false
+                || descriptor.modes.contains(&scene_mode) == false
+                || (descriptor.headless == false && system_parameters.runtime.is_none())
+            {
+                continue;
+            }
+
+            let start = Instant::now();
+            if let Err(e) = (descriptor.run)(system_parameters) {
+                return Err(e.context(format!("The {} system failed", descriptor.name)));
+            }
+            system.timing.record(start.elapsed());
+        }
+
+        // The sync point for this Tick
+        if system_parameters.commands.is_empty() == false {
+            let no_prefabs = PrefabMap::new();
+            let prefabs = match &system_parameters.runtime {
+                Some(runtime) => runtime.resources.prefabs(),
+                None => &no_prefabs,
+            };
+
+            system_parameters.commands.apply(system_parameters.ecs, prefabs);
+        }
+
+        Ok(())
+    }
+
+    pub fn create_imgui_window(&mut self, ui_handler: &mut UiHandler<'_>) -> bool {
+        let mut is_opened = true;
+
+        let ui = &mut ui_handler.ui;
+        let scheduler_window = Window::new(im_str!("Systems"))
+            .size(Vec2::new(420.0, 320.0).into(), imgui::Condition::FirstUseEver)
+            .opened(&mut is_opened);
+
+        if let Some(window) = scheduler_window.begin(ui) {
+            for &(tick, header) in &[(Tick::Fixed, "Fixed Tick"), (Tick::Frame, "Every Frame")] {
+                ui.text(header);
+                ui.columns(3, &im_str!("{}##SystemColumns", header), true);
+                ui.text_disabled("System");
+                ui.next_column();
+                ui.text_disabled("Last (ms)");
+                ui.next_column();
+                ui.text_disabled("Average (ms)");
+                ui.next_column();
+                ui.separator();
+
+                for system in self.systems.iter_mut().filter(|s| s.descriptor.tick == tick) {
+                    ui.checkbox(&im_str!("{}", system.descriptor.name), &mut system.enabled);
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text(format!("Runs in {:?}", system.descriptor.modes));
+                    }
+                    ui.next_column();
+
+                    ui.text(format!("{:.3}", system.timing.last.as_secs_f32() * 1000.0));
+                    ui.next_column();
+
+                    ui.text(format!("{:.3}", system.timing.average_ms));
+                    ui.next_column();
+                }
+
+                ui.columns(1, &im_str!("{}##SystemColumnsEnd", header), false);
+                ui.separator();
+            }
+
+            window.end(ui);
+        }
+
+        is_opened
+    }
+}
+
+fn undo(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if sp.actions.redo {
+        if sp.move_history.undo(
+            sp.grid,
+            &mut sp.ecs.component_database.transforms,
+            &mut sp.ecs.component_database.sprites,
+        ) {
+            sp.level_state.undo_turn();
+        } else {
+            info!("Nothing to undo!");
+        }
+
+        sp.tick_state.board_frozen = true;
+    }
+
+    // Once the Level is over, the board is frozen
+    if sp.level_state.outcome().is_some() {
+        sp.tick_state.board_frozen = true;
+    }
+
+    Ok(())
+}
+
+fn player(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if sp.tick_state.board_frozen == false {
+        player_system::player_update(
+            &mut sp.ecs.component_database.players,
+            &mut sp.ecs.component_database.sprites,
+            &mut sp.ecs.component_database.velocities,
+            sp.actions,
+        );
+    }
+
+    Ok(())
+}
+
+fn grid(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if sp.tick_state.board_frozen == false {
+        sp.tick_state.turn_taken = grid_system::update_grid_positions(sp.ecs, sp.grid, sp.move_history);
+    }
+
+    Ok(())
+}
+
+fn level(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if sp.tick_state.board_frozen == false {
+        level_system::update_level(
+            sp.level_state,
+            sp.tick_state.turn_taken,
+            sp.ecs.singleton_database.level.inner(),
+            &sp.ecs.component_database.grid_objects,
+            sp.grid,
+        );
+    }
+
+    Ok(())
+}
+
+fn follows(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    follow_system::update_follows(
+        &mut sp.ecs.component_database.follows,
+        &mut sp.ecs.component_database.transforms,
+        &sp.ecs.component_database.names,
+        sp.delta_time,
+    );
+
+    Ok(())
+}
+
+fn conversant_npcs(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    conversant_npc_system::update_conversant_npcs(
+        &sp.ecs.component_database.conversant_npcs,
+        &sp.ecs.component_database.transforms,
+        &mut sp.commands,
+    );
+
+    Ok(())
+}
+
+fn sprites(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if let Some(runtime) = &sp.runtime {
+        sprite_system::update_sprites(
+            &mut sp.ecs.component_database.sprites,
+            runtime.resources,
+            sp.delta_time,
+        );
+    }
+
+    Ok(())
+}
+
+fn cross_cutting(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if let Some(runtime) = &sp.runtime {
+        cross_cutting_system::cross_cutting_system(sp.ecs, runtime.resources);
+    }
+
+    Ok(())
+}
+
+/// How often we check `assets/audio` for new or changed sounds.
+const SOUND_RELOAD_TICKS: u64 = 60;
+
+fn sound_reload(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if let Some(runtime) = &mut sp.runtime {
+        if sp.tick_count % SOUND_RELOAD_TICKS == 0 {
+            match runtime.resources.reload_sounds() {
+                Ok(reloaded) => runtime.sound_player.sounds_reloaded(&reloaded),
+                Err(e) => error!("Couldn't reload sounds: {}", e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn sounds(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if let Some(runtime) = &mut sp.runtime {
+        let singleton_database = &sp.ecs.singleton_database;
+        let listener_position = singleton_database.camera.inner().world_position(
+            singleton_database
+                .associated_entities
+                .get(&singleton_database.camera.marker()),
+            &sp.ecs.component_database.transforms,
+        );
+
+        sound_system::play_sounds(
+            &mut sp.ecs.component_database.sound_sources,
+            &sp.ecs.component_database.transforms,
+            listener_position,
+            runtime.sound_player,
+            runtime.resources,
+        );
+    }
+
+    Ok(())
+}
+
+fn music(sp: &mut SystemParameters<'_>) -> Result<(), Error> {
+    if let Some(runtime) = &mut sp.runtime {
+        sound_system::update_music(
+            sp.ecs.singleton_database.music.inner(),
+            runtime.sound_player,
+            runtime.resources,
+            &runtime.resources.config.volume,
+            sp.delta_time,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system(name: &'static str, tick: Tick, after: &'static [&'static str]) -> SystemDescriptor {
+        SystemDescriptor {
+            name,
+            tick,
+            modes: ALL_MODES,
+            after,
+            headless: true,
+            run: |_| Ok(()),
+        }
+    }
+
+    fn schedule(descriptors: Vec<SystemDescriptor>) -> Result<Vec<&'static str>, String> {
+        let descriptors: &'static [SystemDescriptor] = Box::leak(descriptors.into_boxed_slice());
+
+        Scheduler::new(descriptors)
+            .map(|scheduler| scheduler.systems.iter().map(|s| s.descriptor.name).collect())
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn every_system_in_the_game_can_be_scheduled() {
+        assert!(Scheduler::new(SYSTEMS).is_ok());
+    }
+
+    #[test]
+    fn systems_run_after_what_they_wait_on_and_otherwise_in_list_order() {
+        let order = schedule(vec![
+            system("c", Tick::Fixed, &["b"]),
+            system("a", Tick::Fixed, &[]),
+            system("b", Tick::Fixed, &["a"]),
+            system("d", Tick::Fixed, &[]),
+            system("e", Tick::Frame, &[]),
+        ]);
+
+        assert_eq!(order, Ok(vec!["a", "b", "c", "d", "e"]));
+    }
+
+    #[test]
+    fn systems_which_wait_on_each_other_are_rejected() {
+        let error = schedule(vec![
+            system("a", Tick::Fixed, &[]),
+            system("b", Tick::Fixed, &["c"]),
+            system("c", Tick::Fixed, &["b"]),
+        ])
+        .unwrap_err();
+
+        assert!(error.contains("wait on each other: b, c"), "{}", error);
+    }
+
+    #[test]
+    fn waiting_on_a_missing_system_is_rejected() {
+        let error = schedule(vec![system("a", Tick::Fixed, &["z"])]).unwrap_err();
+
+        assert!(error.contains("no system by that name"), "{}", error);
+    }
+
+    #[test]
+    fn two_systems_with_one_name_are_rejected() {
+        let error = schedule(vec![system("a", Tick::Fixed, &[]), system("a", Tick::Frame, &[])]).unwrap_err();
+
+        assert!(error.contains("two systems named a"), "{}", error);
+    }
+
+    #[test]
+    fn waiting_on_a_system_on_another_tick_is_rejected() {
+        let error = schedule(vec![
+            system("a", Tick::Frame, &[]),
+            system("b", Tick::Fixed, &["a"]),
+        ])
+        .unwrap_err();
+
+        assert!(error.contains("don't run on the same Tick"), "{}", error);
+    }
+}
@@ -1,13 +1,14 @@
 pub use super::*;
 
 mod fragmented_data;
+mod load_error;
+pub mod migrations;
+pub mod safe_files;
 pub mod serialization_util;
 mod serialized_entity;
 
-#[cfg(debug_assertions)]
-pub mod update_serialization;
-
 pub use fragmented_data::FragmentedData;
+pub use load_error::*;
 pub use serialized_entity::*;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -0,0 +1,177 @@
+use super::{
+    migrations::{self, yaml_key_name, SerializedFileKind},
+    SerializedEntity,
+};
+use serde_yaml::{Mapping, Value as YamlValue};
+use std::sync::atomic::{AtomicBool, Ordering};
+use uuid::Uuid;
+
+static RECOVERY_MODE: AtomicBool = AtomicBool::new(false);
+
+/// In recovery mode, a Scene or Prefab with broken entities in it loads every entity
+/// which is fine, and reports the rest, instead of failing to load at all.
+/// The file is copied to `<file>.broken` first, so nothing is lost on the next save.
+pub fn set_recovery_mode(recovery_mode: bool) {
+    RECOVERY_MODE.store(recovery_mode, Ordering::Relaxed);
+}
+
+pub fn recovery_mode() -> bool {
+    RECOVERY_MODE.load(Ordering::Relaxed)
+}
+
+/// Why a file couldn't be loaded.
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    /// The file isn't valid YAML.
+    #[error("{path}:{line}:{column}: {message}")]
+    Syntax {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The file is valid YAML, but it isn't what we expected.
+    #[error("{path}: {message}")]
+    Invalid { path: String, message: String },
+    /// Some of the entities in a Scene or Prefab are broken.
+    #[error("{path} has entities we couldn't load:{errors}")]
+    Entities { path: String, errors: EntityLoadErrors },
+}
+
+impl LoadError {
+    pub fn from_yaml(path: &str, error: serde_yaml::Error) -> Self {
+        match error.location() {
+            Some(location) => LoadError::Syntax {
+                path: path.to_string(),
+                line: location.line(),
+                column: location.column(),
+                message: error.to_string(),
+            },
+            None => LoadError::Invalid {
+                path: path.to_string(),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+/// An entity in a Scene or Prefab which couldn't be loaded.
+#[derive(Debug)]
+pub struct EntityLoadError {
+    key: YamlValue,
+    pub id: String,
+    /// The component (or other field) which is broken, if we could narrow it down.
+    pub field: Option<String>,
+    /// Where the entity, or its broken field, is in the file.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for EntityLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        write!(f, "entity {}", self.id)?;
+        if let Some(field) = &self.field {
+            write!(f, ", in {}", field)?;
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Debug)]
+pub struct EntityLoadErrors(pub Vec<EntityLoadError>);
+
+impl std::fmt::Display for EntityLoadErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for error in self.0.iter() {
+            write!(f, "\n    {}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds every SerializedEntity in the file which won't deserialize, and works out
+/// which of its fields is to blame. `file_string` is only used to find line numbers.
+pub fn find_entity_errors(
+    yaml: &YamlValue,
+    kind: SerializedFileKind,
+    file_string: &str,
+) -> Vec<EntityLoadError> {
+    let entities = match migrations::serialized_entities(yaml, kind) {
+        Some(entities) => entities,
+        None => return vec![],
+    };
+
+    let mut errors = vec![];
+    for (key, entity) in entities.iter() {
+        let error = match serde_yaml::from_value::<Uuid>(key.clone()) {
+            Ok(_) => serde_yaml::from_value::<SerializedEntity>(entity.clone())
+                .err()
+                .map(|e| (broken_field(entity), e.to_string())),
+            Err(e) => Some((None, format!("its id isn't a UUID ({})", e))),
+        };
+
+        if let Some((field, message)) = error {
+            let id = yaml_key_name(key);
+            let line = find_line(file_string, &id, field.as_deref());
+
+            errors.push(EntityLoadError {
+                key: key.clone(),
+                id,
+                field,
+                line,
+                message,
+            });
+        }
+    }
+
+    errors
+}
+
+/// Takes the broken entities out of the file, so the rest of it will load.
+pub fn remove_broken_entities(yaml: &mut YamlValue, kind: SerializedFileKind, errors: &[EntityLoadError]) {
+    if let Some(entities) = migrations::serialized_entities_mut(yaml, kind) {
+        for error in errors {
+            entities.remove(&error.key);
+        }
+    }
+}
+
+// SerializedEntity is `#[serde(default)]`, so we can try each field on its own.
+fn broken_field(entity: &YamlValue) -> Option<String> {
+    entity
+        .as_mapping()?
+        .iter()
+        .find(|(key, value)| {
+            let mut alone = Mapping::new();
+            alone.insert((*key).clone(), (*value).clone());
+
+            serde_yaml::from_value::<SerializedEntity>(YamlValue::Mapping(alone)).is_err()
+        })
+        .map(|(key, _)| yaml_key_name(key))
+}
+
+// The line the entity starts on, or the line of its field, if we have one. We search
+// the text for these, since serde_yaml can't tell us where a Value came from.
+fn find_line(file_string: &str, id: &str, field: Option<&str>) -> Option<usize> {
+    let mut lines = file_string.lines().enumerate().skip_while(|(_, line)| {
+        line.trim_start()
+            .trim_start_matches(|c| c == '"' || c == '\'')
+            .starts_with(id)
+            == false
+    });
+    let (entity_line, _) = lines.next()?;
+
+    let field_line = field.and_then(|field| {
+        let field = format!("{}:", field);
+        lines
+            .find(|(_, line)| line.trim_start().starts_with(&field))
+            .map(|(i, _)| i)
+    });
+
+    Some(field_line.unwrap_or(entity_line) + 1)
+}
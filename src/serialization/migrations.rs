@@ -0,0 +1,480 @@
+use super::{
+    serialization_util, Prefab, SerializedEntity, SingletonDatabase, ENTITY_DIRECTORY_SUBPATH,
+    ENTITY_SUBPATH, PREFAB_DIRECTORY, SCENE_DIRECTORY, SINGLETONS_SUBPATH,
+};
+use anyhow::{Context, Error};
+use serde_yaml::{Mapping, Value as YamlValue};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The format version every Scene, Prefab and Singleton file is saved at.
+/// Bump this whenever one of those formats changes, and add a Migration for it.
+pub const CURRENT_FORMAT_VERSION: u64 = 1;
+const FORMAT_VERSION_KEY: &str = "format_version";
+
+/// `MIGRATIONS[n]` takes a file from format version `n` to `n + 1`. Files
+/// written before we had versions are version 0.
+///
+/// Renaming or restructuring a component's fields needs a Migration. Without one,
+/// `#[serde(default)]` quietly fills the new field in and the old data is dropped.
+const MIGRATIONS: [Migration; CURRENT_FORMAT_VERSION as usize] = [Migration {
+    description: "components are `inner`/`active` mappings, and Scenes are keyed by entity id",
+    migrate: migrate_to_keyed_entities,
+}];
+
+/// The kinds of file which carry a format version.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SerializedFileKind {
+    /// A Scene's entities all in one file, which is a map of ids to SerializedEntities.
+    /// Scenes are split up into `Entity` files the next time they're saved.
+    Entities,
+    /// A single SerializedEntity, out of a Scene's entity directory.
+    Entity,
+    /// A Prefab, which holds its SerializedEntities in `members`.
+    Prefab,
+    Singletons,
+}
+
+struct Migration {
+    description: &'static str,
+    migrate: fn(&mut YamlValue, SerializedFileKind) -> Result<(), Error>,
+}
+
+/// Takes the format version off of a freshly loaded file and runs every Migration
+/// it's missing, leaving the file ready to deserialize.
+pub fn migrate_to_current(yaml: &mut YamlValue, kind: SerializedFileKind, path: &str) -> Result<(), Error> {
+    let version = take_format_version(yaml)?;
+    if version > CURRENT_FORMAT_VERSION {
+        bail!(
+            "{} is format version {}, but we only know up to version {}. Was it saved by a newer build?",
+            path,
+            version,
+            CURRENT_FORMAT_VERSION
+        );
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        (migration.migrate)(yaml, kind).with_context(|| {
+            format!(
+                "Couldn't migrate {} to format version {} ({})",
+                path,
+                i + 1,
+                migration.description
+            )
+        })?;
+        info!("Migrated {} to format version {}", path, i + 1);
+    }
+
+    Ok(())
+}
+
+/// Removes the format version from a file, giving 0 if it didn't have one.
+pub fn take_format_version(yaml: &mut YamlValue) -> Result<u64, Error> {
+    let version = match yaml {
+        YamlValue::Mapping(mapping) => mapping.remove(&YamlValue::from(FORMAT_VERSION_KEY)),
+        _ => None,
+    };
+
+    match version {
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| anyhow!("{} should be a number, but was {:?}", FORMAT_VERSION_KEY, version)),
+        None => Ok(0),
+    }
+}
+
+/// Stamps the current format version onto a file which is about to be saved.
+pub fn add_format_version(yaml: &mut YamlValue) {
+    if let YamlValue::Mapping(mapping) = yaml {
+        // We put it at the top, where it's easy to see
+        let mut versioned = Mapping::with_capacity(mapping.len() + 1);
+        versioned.insert(
+            YamlValue::from(FORMAT_VERSION_KEY),
+            YamlValue::from(CURRENT_FORMAT_VERSION),
+        );
+        for (key, value) in std::mem::replace(mapping, Mapping::new()) {
+            versioned.insert(key, value);
+        }
+
+        *mapping = versioned;
+    }
+}
+
+/// Logs every field which is in the file, but which didn't survive being loaded
+/// and saved again. Those are fields we don't know about anymore, so they're
+/// about to be lost -- which means a Migration is missing.
+pub fn report_dropped_fields(loaded: &YamlValue, round_tripped: &YamlValue, path: &str) {
+    let mut dropped = vec![];
+    find_dropped_fields(loaded, round_tripped, &mut String::new(), &mut dropped);
+
+    for field in dropped {
+        error!(
+            "{} has a field {}, which we don't know about. It will be lost on the next save!",
+            path, field
+        );
+    }
+}
+
+fn find_dropped_fields(
+    loaded: &YamlValue,
+    round_tripped: &YamlValue,
+    at: &mut String,
+    dropped: &mut Vec<String>,
+) {
+    if let (YamlValue::Mapping(loaded), YamlValue::Mapping(round_tripped)) = (loaded, round_tripped) {
+        for (key, value) in loaded.iter() {
+            if value.is_null() {
+                continue;
+            }
+
+            let previous_length = at.len();
+            at.push('/');
+            at.push_str(&yaml_key_name(key));
+
+            match round_tripped.get(key) {
+                Some(round_tripped_value) => find_dropped_fields(value, round_tripped_value, at, dropped),
+                None => dropped.push(at.clone()),
+            }
+
+            at.truncate(previous_length);
+        }
+    }
+}
+
+/// The map of ids to SerializedEntities in the file, if it has one.
+pub fn serialized_entities(yaml: &YamlValue, kind: SerializedFileKind) -> Option<&Mapping> {
+    match kind {
+        SerializedFileKind::Entities => yaml.as_mapping(),
+        SerializedFileKind::Prefab => yaml.get("members")?.as_mapping(),
+        SerializedFileKind::Entity | SerializedFileKind::Singletons => None,
+    }
+}
+
+/// The map of ids to SerializedEntities in the file, if it has one.
+pub fn serialized_entities_mut(yaml: &mut YamlValue, kind: SerializedFileKind) -> Option<&mut Mapping> {
+    match kind {
+        SerializedFileKind::Entities => yaml.as_mapping_mut(),
+        SerializedFileKind::Prefab => yaml.get_mut("members")?.as_mapping_mut(),
+        SerializedFileKind::Entity | SerializedFileKind::Singletons => None,
+    }
+}
+
+/// A mapping key as it's written in the file, which is usually just the string.
+pub fn yaml_key_name(key: &YamlValue) -> String {
+    match key {
+        YamlValue::String(key) => key.clone(),
+        key => format!("{:?}", key),
+    }
+}
+
+/// Calls `f` on every SerializedEntity in the file.
+pub fn for_each_serialized_entity(
+    yaml: &mut YamlValue,
+    kind: SerializedFileKind,
+    mut f: impl FnMut(&mut Mapping) -> Result<(), Error>,
+) -> Result<(), Error> {
+    if kind == SerializedFileKind::Entity {
+        if let YamlValue::Mapping(entity) = yaml {
+            f(entity)?;
+        }
+
+        return Ok(());
+    }
+
+    if let Some(entities) = serialized_entities_mut(yaml, kind) {
+        for (_, entity) in entities.iter_mut() {
+            if let YamlValue::Mapping(entity) = entity {
+                f(entity)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Renames a field inside of a component on every SerializedEntity in the file.
+/// This is what most Migrations need.
+#[allow(dead_code)]
+pub fn rename_component_field(
+    yaml: &mut YamlValue,
+    kind: SerializedFileKind,
+    component_key: &str,
+    old_field: &str,
+    new_field: &str,
+) -> Result<(), Error> {
+    for_each_serialized_entity(yaml, kind, |entity| {
+        let inner = entity
+            .get_mut(&YamlValue::from(component_key))
+            .and_then(|component| component.as_mapping_mut())
+            .and_then(|component| component.get_mut(&YamlValue::from("inner")))
+            .and_then(|inner| inner.as_mapping_mut());
+
+        if let Some(inner) = inner {
+            if let Some(value) = inner.remove(&YamlValue::from(old_field)) {
+                inner.insert(YamlValue::from(new_field), value);
+            }
+        }
+
+        Ok(())
+    })
+}
+
+// Version 0 -> 1. Components used to be saved as `[inner, active]` pairs, and
+// Scenes as a list of entities rather than a map of them.
+fn migrate_to_keyed_entities(yaml: &mut YamlValue, kind: SerializedFileKind) -> Result<(), Error> {
+    if kind == SerializedFileKind::Entities {
+        if let YamlValue::Sequence(entities) = yaml {
+            let mut keyed_entities = Mapping::new();
+            for entity in entities.drain(..) {
+                let id = entity
+                    .get("id")
+                    .cloned()
+                    .ok_or_else(|| anyhow!("an entity in the list has no id"))?;
+                keyed_entities.insert(id, entity);
+            }
+
+            *yaml = YamlValue::Mapping(keyed_entities);
+        }
+    }
+
+    for_each_serialized_entity(yaml, kind, |entity| {
+        for (key, component) in entity.iter_mut() {
+            if key.as_str() == Some("id") || key.as_str() == Some("marker") {
+                continue;
+            }
+
+            let pair = match component {
+                YamlValue::Sequence(pair) if pair.len() == 2 && pair[1].is_bool() => pair,
+                _ => continue,
+            };
+
+            let active = pair.pop().unwrap();
+            let inner = pair.pop().unwrap();
+
+            let mut wrapper = Mapping::new();
+            wrapper.insert(YamlValue::from("inner"), inner);
+            wrapper.insert(YamlValue::from("active"), active);
+            *component = YamlValue::Mapping(wrapper);
+        }
+
+        Ok(())
+    })
+}
+
+/// Handles `--migrate`, which brings every Scene, Prefab and Singleton file in
+/// `assets/serialized_data` up to the current format version, in place.
+/// Returns None if it wasn't asked for.
+pub fn run_from_args(args: &[String]) -> Option<Result<bool, Error>> {
+    if args.iter().any(|arg| arg == "--migrate") == false {
+        return None;
+    }
+
+    Some(migrate_all_files().map(|migrated| {
+        println!(
+            "Migrated {} files to format version {}",
+            migrated, CURRENT_FORMAT_VERSION
+        );
+        true
+    }))
+}
+
+fn migrate_all_files() -> Result<usize, Error> {
+    let mut files = vec![];
+
+    for entry in std::fs::read_dir(SCENE_DIRECTORY)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.push((path.join(ENTITY_SUBPATH), SerializedFileKind::Entities));
+            files.push((path.join(SINGLETONS_SUBPATH), SerializedFileKind::Singletons));
+
+            let entity_directory = path.join(ENTITY_DIRECTORY_SUBPATH);
+            if entity_directory.is_dir() {
+                for entry in std::fs::read_dir(entity_directory)? {
+                    let entity_path = entry?.path();
+                    if entity_path
+                        .extension()
+                        .map_or(false, |extension| extension == "yaml")
+                    {
+                        files.push((entity_path, SerializedFileKind::Entity));
+                    }
+                }
+            }
+        }
+    }
+
+    for path in glob::glob(&format!("{}/**/*.prefab", PREFAB_DIRECTORY))? {
+        files.push((path?, SerializedFileKind::Prefab));
+    }
+
+    for path in glob::glob(&format!("{}/*.yaml", PREFAB_DIRECTORY))? {
+        files.push((path?, SerializedFileKind::Singletons));
+    }
+
+    let mut migrated = 0;
+    for (path, kind) in files {
+        if path.exists() == false {
+            continue;
+        }
+
+        let path = path.to_str().unwrap();
+        if migrate_file(path, kind).with_context(|| format!("Couldn't migrate {}", path))? {
+            migrated += 1;
+        }
+    }
+
+    Ok(migrated)
+}
+
+/// Loads a file and saves it straight back out, which migrates it. Returns false
+/// if it was already at the current format version.
+fn migrate_file(path: &str, kind: SerializedFileKind) -> Result<bool, Error> {
+    let file_string = std::fs::read_to_string(path)?;
+    if file_string.trim().is_empty() {
+        return Ok(false);
+    }
+
+    let mut yaml: YamlValue = serde_yaml::from_str(&file_string)?;
+    if take_format_version(&mut yaml)? == CURRENT_FORMAT_VERSION {
+        return Ok(false);
+    }
+
+    match kind {
+        SerializedFileKind::Entities => {
+            let entities: HashMap<Uuid, SerializedEntity> =
+                serialization_util::load_versioned_file(path, kind)?;
+            serialization_util::save_versioned_file(&entities, path)?;
+        }
+        SerializedFileKind::Entity => {
+            let entity: SerializedEntity = serialization_util::load_versioned_file(path, kind)?;
+            serialization_util::save_versioned_file(&entity, path)?;
+        }
+        SerializedFileKind::Prefab => {
+            let prefab: Prefab = serialization_util::load_versioned_file(path, kind)?;
+            serialization_util::save_versioned_file(&prefab, path)?;
+        }
+        SerializedFileKind::Singletons => {
+            let singletons: SingletonDatabase = serialization_util::load_versioned_file(path, kind)?;
+            serialization_util::save_versioned_file(&singletons, path)?;
+        }
+    }
+
+    println!("{}: migrated", path);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> YamlValue {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn unversioned_scenes_are_migrated_to_the_current_version() {
+        let mut entities = yaml(
+            "
+- id: a
+  name: [{name: Player}, true]
+  transform: [{}, false]
+- id: b
+  marker: Camera
+",
+        );
+
+        migrate_to_current(&mut entities, SerializedFileKind::Entities, "entities.yaml").unwrap();
+
+        assert_eq!(
+            entities,
+            yaml(
+                "
+a:
+  id: a
+  name: {inner: {name: Player}, active: true}
+  transform: {inner: {}, active: false}
+b:
+  id: b
+  marker: Camera
+"
+            )
+        );
+    }
+
+    #[test]
+    fn files_at_the_current_version_are_left_alone() {
+        let mut singletons = yaml("{format_version: 1, camera: [{}, true]}");
+
+        migrate_to_current(&mut singletons, SerializedFileKind::Singletons, "singletons.yaml").unwrap();
+
+        assert_eq!(singletons, yaml("{camera: [{}, true]}"));
+    }
+
+    #[test]
+    fn files_from_a_newer_version_are_rejected() {
+        let mut singletons = yaml(&format!("format_version: {}", CURRENT_FORMAT_VERSION + 1));
+
+        let error = migrate_to_current(&mut singletons, SerializedFileKind::Singletons, "singletons.yaml")
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Was it saved by a newer build?"), "{}", error);
+    }
+
+    #[test]
+    fn component_fields_are_renamed_in_every_entity() {
+        let mut prefab = yaml(
+            "
+members:
+  a:
+    sprite: {inner: {sprite_name: Bot, frame: 2}, active: true}
+  b:
+    name: {inner: {name: Bot}, active: true}
+",
+        );
+
+        rename_component_field(
+            &mut prefab,
+            SerializedFileKind::Prefab,
+            "sprite",
+            "sprite_name",
+            "name",
+        )
+        .unwrap();
+
+        assert_eq!(
+            prefab,
+            yaml(
+                "
+members:
+  a:
+    sprite: {inner: {frame: 2, name: Bot}, active: true}
+  b:
+    name: {inner: {name: Bot}, active: true}
+"
+            )
+        );
+    }
+
+    #[test]
+    fn fields_which_dont_survive_a_round_trip_are_reported() {
+        let loaded = yaml(
+            "
+id: a
+sprite: {inner: {name: Bot, old_frame: 2}, active: true}
+follow: ~
+retired_component: {inner: {}, active: true}
+",
+        );
+        let round_tripped = yaml(
+            "
+id: a
+sprite: {inner: {name: Bot}, active: true}
+",
+        );
+
+        let mut dropped = vec![];
+        find_dropped_fields(&loaded, &round_tripped, &mut String::new(), &mut dropped);
+
+        assert_eq!(dropped, vec!["/sprite/inner/old_frame", "/retired_component"]);
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::{Context, Error};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const BACKUP_DIRECTORY: &str = "backups";
+
+static BACKUP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// How many old versions of each file we keep when we overwrite or remove it. They go
+/// in a `backups` directory beside the file, so every Scene gets its own. With 0, we
+/// keep just the one, in `<file>.bak`.
+pub fn set_backup_count(backup_count: usize) {
+    BACKUP_COUNT.store(backup_count, Ordering::Relaxed);
+}
+
+pub fn backup_count() -> usize {
+    BACKUP_COUNT.load(Ordering::Relaxed)
+}
+
+/// Writes the file by way of `<file>.tmp`, which is renamed over it once it's all
+/// on disk. A crash partway through leaves the old file as it was, rather than
+/// half of the new one.
+pub fn write_atomically(path: &str, contents: &[u8]) -> Result<(), Error> {
+    let file_path = Path::new(path);
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = format!("{}.tmp", path);
+    let mut temp_file =
+        fs::File::create(&temp_path).with_context(|| format!("Couldn't create {}", temp_path))?;
+    temp_file.write_all(contents)?;
+    temp_file.sync_all()?;
+
+    fs::rename(&temp_path, file_path)
+        .with_context(|| format!("Couldn't move {} onto {}", temp_path, path))?;
+    Ok(())
+}
+
+/// Backs the file up and then removes it. Does nothing if there's no file.
+pub fn remove_file(path: &str) -> Result<(), Error> {
+    if Path::new(path).exists() == false {
+        return Ok(());
+    }
+
+    back_up_file(path)?;
+    fs::remove_file(path).with_context(|| format!("Couldn't remove {}", path))?;
+    Ok(())
+}
+
+/// Copies the file into its backups, dropping the oldest one if there are now too many.
+pub fn back_up_file(path: &str) -> Result<(), Error> {
+    let backup_count = backup_count();
+    if backup_count == 0 {
+        fs::copy(path, format!("{}.bak", path))?;
+        return Ok(());
+    }
+
+    let file_path = Path::new(path);
+    let file_name = file_path.file_name().unwrap().to_string_lossy().to_string();
+    let backup_directory = file_path.parent().map_or_else(
+        || PathBuf::from(BACKUP_DIRECTORY),
+        |parent| parent.join(BACKUP_DIRECTORY),
+    );
+    fs::create_dir_all(&backup_directory)?;
+
+    // Two saves in the same millisecond would share a name, so the second one takes
+    // the next free millisecond instead of writing over the first
+    let mut now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+    while backup_directory
+        .join(format!("{}.{}.bak", file_name, now))
+        .exists()
+    {
+        now += 1;
+    }
+    fs::copy(
+        file_path,
+        backup_directory.join(format!("{}.{}.bak", file_name, now)),
+    )?;
+
+    // Backups are named `<file>.<time>.bak`, so we can find the oldest by their names
+    let prefix = format!("{}.", file_name);
+    let mut backups = vec![];
+    for entry in fs::read_dir(&backup_directory)? {
+        let entry_name = entry?.file_name().to_string_lossy().to_string();
+        if entry_name.starts_with(&prefix) == false || entry_name.ends_with(".bak") == false {
+            continue;
+        }
+
+        let time = entry_name
+            .get(prefix.len()..entry_name.len() - ".bak".len())
+            .and_then(|time| time.parse::<u128>().ok());
+        if let Some(time) = time {
+            backups.push((time, entry_name));
+        }
+    }
+
+    backups.sort();
+    let excess = backups.len().saturating_sub(backup_count);
+    for (_, old_backup) in backups.into_iter().take(excess) {
+        fs::remove_file(backup_directory.join(old_backup))?;
+    }
+
+    Ok(())
+}
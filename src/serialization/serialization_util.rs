@@ -1,8 +1,13 @@
 use super::*;
 
-use anyhow::{Error, Result as AnyResult};
+use anyhow::{Context, Error, Result as AnyResult};
 use std::fs;
 
+mod serialize_cooked;
+pub mod cooked {
+    pub use super::serialize_cooked::*;
+}
+
 mod serialize_entities;
 pub mod entities {
     pub use super::serialize_entities::*;
@@ -13,11 +18,26 @@ pub mod game_config {
     pub use super::serialize_game_config::*;
 }
 
+mod serialize_input_bindings;
+pub mod input_bindings {
+    pub use super::serialize_input_bindings::*;
+}
+
+mod serialize_interaction_rules;
+pub mod interaction_rules {
+    pub use super::serialize_interaction_rules::*;
+}
+
 mod serialize_prefabs;
 pub mod prefabs {
     pub use super::serialize_prefabs::*;
 }
 
+mod serialize_replays;
+pub mod replays {
+    pub use super::serialize_replays::*;
+}
+
 mod serialize_singleton_components;
 pub mod singleton_components {
     pub use super::serialize_singleton_components::*;
@@ -42,51 +62,125 @@ pub(super) fn load_serialized_file<T: Default>(path: &str) -> Result<T, Error>
 where
     for<'de> T: serde::Deserialize<'de>,
 {
-    let path = std::path::Path::new(path);
-    fs::create_dir_all(path.parent().unwrap())?;
-
-    if path.exists() == false {
-        fs::File::create(path)?;
+    if let Some(item) = cooked::load_cooked(path)? {
+        return Ok(item);
     }
 
-    let file_string = fs::read_to_string(path)?;
-    Ok(serde_yaml::from_str(&file_string).unwrap_or_default())
+    match read_file(path)? {
+        Some(file_string) => {
+            Ok(serde_yaml::from_str(&file_string).map_err(|e| LoadError::from_yaml(path, e))?)
+        }
+        None => Ok(T::default()),
+    }
 }
 
+/// Saves the file, first backing up what it's overwriting. The write is atomic,
+/// so a crash while saving can't leave a half written file behind.
 pub fn save_serialized_file<T>(item: &T, path: &str) -> Result<(), Error>
 where
     T: serde::Serialize,
 {
+    cooked::forget_cooked(path);
+
     let s = serde_yaml::to_string(item)?;
-    Ok(fs::write(path, s)?)
-}
-
-// fn load_file_bin<T: Default>(path: &str) -> Result<T, Error>
-// where
-//     for<'de> T: serde::Deserialize<'de>,
-// {
-//     let path = std::path::Path::new(path);
-//     fs::create_dir_all(path.parent().unwrap())?;
-
-//     if path.exists() == false {
-//         fs::File::create(path)?;
-//     }
-
-//     let file_bits: Vec<u8> = fs::read(path)?;
-//     Ok(bincode::deserialize(&file_bits).unwrap_or_default())
-// }
-
-// fn save_file_bin<T>(item: &T, path: &str) -> Result<(), Error>
-// where
-//     T: serde::Serialize,
-// {
-//     let path = std::path::Path::new(path);
-//     fs::create_dir_all(path.parent().unwrap())?;
-
-//     if path.exists() == false {
-//         fs::File::create(path)?;
-//     }
-
-//     let s = bincode::serialize(item)?;
-//     Ok(fs::write(path, s)?)
-// }
+    if std::path::Path::new(path).exists() {
+        if fs::read_to_string(path).map_or(false, |old| old == s) {
+            return Ok(());
+        }
+
+        safe_files::back_up_file(path)?;
+    }
+
+    safe_files::write_atomically(path, s.as_bytes())
+}
+
+/// Removes the file, backing it up first.
+pub fn remove_serialized_file(path: &str) -> Result<(), Error> {
+    cooked::forget_cooked(path);
+
+    safe_files::remove_file(path)
+}
+
+/// Loads a Scene, Prefab or Singleton file, migrating it up from whatever format
+/// version it was saved at. If some of its entities are broken, this fails with
+/// a `LoadError::Entities`, unless we're in recovery mode. Cooked files are already
+/// migrated, so they're loaded as they are.
+pub(super) fn load_versioned_file<T: Default>(
+    path: &str,
+    kind: migrations::SerializedFileKind,
+) -> Result<T, Error>
+where
+    for<'de> T: serde::Deserialize<'de> + serde::Serialize,
+{
+    if let Some(item) = cooked::load_cooked(path)? {
+        return Ok(item);
+    }
+
+    let file_string = match read_file(path)? {
+        Some(file_string) => file_string,
+        None => return Ok(T::default()),
+    };
+
+    let mut yaml: serde_yaml::Value =
+        serde_yaml::from_str(&file_string).map_err(|e| LoadError::from_yaml(path, e))?;
+    migrations::migrate_to_current(&mut yaml, kind, path)?;
+
+    let item: T = match serde_yaml::from_value(yaml.clone()) {
+        Ok(item) => item,
+        Err(e) => {
+            let errors = find_entity_errors(&yaml, kind, &file_string);
+            if errors.is_empty() {
+                return Err(LoadError::from_yaml(path, e).into());
+            }
+
+            if recovery_mode() == false {
+                return Err(LoadError::Entities {
+                    path: path.to_string(),
+                    errors: EntityLoadErrors(errors),
+                }
+                .into());
+            }
+
+            let broken_path = format!("{}.broken", path);
+            fs::copy(path, &broken_path)?;
+            remove_broken_entities(&mut yaml, kind, &errors);
+            error!(
+                "Recovered {}, leaving out {} broken entities. The original is in {}.{}",
+                path,
+                errors.len(),
+                broken_path,
+                EntityLoadErrors(errors)
+            );
+
+            serde_yaml::from_value(yaml.clone()).map_err(|e| LoadError::from_yaml(path, e))?
+        }
+    };
+    migrations::report_dropped_fields(&yaml, &serde_yaml::to_value(&item)?, path);
+
+    Ok(item)
+}
+
+/// Saves a Scene, Prefab or Singleton file at the current format version.
+pub fn save_versioned_file<T>(item: &T, path: &str) -> Result<(), Error>
+where
+    T: serde::Serialize,
+{
+    let mut yaml = serde_yaml::to_value(item)?;
+    migrations::add_format_version(&mut yaml);
+
+    save_serialized_file(&yaml, path)
+}
+
+/// The contents of a file, or None if it's missing or blank.
+fn read_file(path: &str) -> Result<Option<String>, Error> {
+    if std::path::Path::new(path).exists() == false {
+        return Ok(None);
+    }
+
+    let file_string = fs::read_to_string(path).with_context(|| format!("Couldn't read {}", path))?;
+    if file_string.trim().is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(file_string))
+    }
+}
@@ -0,0 +1,195 @@
+use super::{
+    migrations::{SerializedFileKind, CURRENT_FORMAT_VERSION},
+    serialize_sprites::{SPRITESHEET_PATH, SPRITE_PATH},
+    serialize_tilesets::TILESET_PATH,
+    sprite_resources::SpriteInGameData,
+    tile_resources::TileSetSerialized,
+    *,
+};
+use anyhow::Context;
+use clockwork_build_shared::sprite_packing::shared::SpriteSheet;
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, path::Path, sync::Mutex};
+
+pub const COOKED_BUNDLE_PATH: &str = "assets/cooked/bundle.bin";
+
+/// Every Scene, Prefab, sprite and tileset file, deserialized out of its YAML and
+/// packed together as bincode. Shipping builds load from this instead of the YAML,
+/// which is much faster and doesn't need the YAML to be around.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CookedBundle {
+    format_version: u64,
+    /// The bincode for each file, keyed by the path of the YAML it was cooked from.
+    files: HashMap<String, Vec<u8>>,
+}
+
+// Release builds load from the bundle, if there is one. Debug builds always use the YAML.
+static BUNDLE: Lazy<Mutex<Option<CookedBundle>>> = Lazy::new(|| {
+    Mutex::new(if cfg!(debug_assertions) {
+        None
+    } else {
+        read_bundle()
+    })
+});
+
+fn read_bundle() -> Option<CookedBundle> {
+    let bytes = fs::read(COOKED_BUNDLE_PATH).ok()?;
+
+    match bincode::deserialize::<CookedBundle>(&bytes) {
+        Ok(bundle) if bundle.format_version == CURRENT_FORMAT_VERSION => {
+            info!("Loading from the cooked bundle at {}", COOKED_BUNDLE_PATH);
+            Some(bundle)
+        }
+        Ok(bundle) => {
+            error!(
+                "The cooked bundle is format version {}, but we're on version {}. Loading the YAML instead -- cook it again with --cook!",
+                bundle.format_version, CURRENT_FORMAT_VERSION
+            );
+            None
+        }
+        Err(e) => {
+            error!(
+                "We couldn't read the cooked bundle at {}, so we're loading the YAML instead: {}",
+                COOKED_BUNDLE_PATH, e
+            );
+            None
+        }
+    }
+}
+
+/// The cooked version of the file at `path`, if we're loading from a bundle and it has one.
+pub(super) fn load_cooked<T>(path: &str) -> Result<Option<T>, Error>
+where
+    for<'de> T: serde::Deserialize<'de>,
+{
+    let bundle = BUNDLE.lock().unwrap();
+
+    match bundle.as_ref().and_then(|bundle| bundle.files.get(path)) {
+        Some(bytes) => {
+            Ok(Some(bincode::deserialize(bytes).with_context(|| {
+                format!("Couldn't read {} out of the cooked bundle", path)
+            })?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Whether we're loading the file at `path` out of the bundle.
+pub(super) fn is_cooked(path: &str) -> bool {
+    BUNDLE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(false, |bundle| bundle.files.contains_key(path))
+}
+
+/// Takes a file out of the bundle once its YAML has been saved over, so that we
+/// load the new version from then on.
+pub(super) fn forget_cooked(path: &str) {
+    if let Some(bundle) = BUNDLE.lock().unwrap().as_mut() {
+        bundle.files.remove(path);
+    }
+}
+
+/// Every cooked file in `directory` (or below it) which ends in `extension`.
+/// This is None if we aren't loading from a bundle.
+pub fn cooked_paths(directory: &str, extension: &str) -> Option<Vec<String>> {
+    let bundle = BUNDLE.lock().unwrap();
+
+    bundle.as_ref().map(|bundle| {
+        bundle
+            .files
+            .keys()
+            .filter(|path| path.starts_with(directory) && path.ends_with(extension))
+            .cloned()
+            .collect()
+    })
+}
+
+/// Handles `--cook`, which packs every Scene, Prefab, sprite and tileset file into
+/// the bundle that release builds load from. Returns None if it wasn't asked for.
+pub fn run_from_args(args: &[String]) -> Option<Result<bool, Error>> {
+    if args.iter().any(|arg| arg == "--cook") == false {
+        return None;
+    }
+
+    Some(cook_bundle().map(|bundle| {
+        println!("Cooked {} files into {}", bundle.files.len(), COOKED_BUNDLE_PATH);
+        true
+    }))
+}
+
+/// Loads every file the game needs out of its YAML, migrating it if needs be,
+/// and writes them all into the bundle at `COOKED_BUNDLE_PATH`.
+pub fn cook_bundle() -> Result<CookedBundle, Error> {
+    // We're cooking from the YAML, so we mustn't load anything out of an old bundle
+    BUNDLE.lock().unwrap().take();
+
+    let mut bundle = CookedBundle {
+        format_version: CURRENT_FORMAT_VERSION,
+        files: HashMap::new(),
+    };
+
+    for entry in fs::read_dir(SCENE_DIRECTORY)? {
+        let entry = entry?;
+        if entry.path().is_dir() == false {
+            continue;
+        }
+
+        let scene = Scene::new(entry.file_name().to_string_lossy().to_string());
+        // A Scene's entities might be one file or a file each, but they're cooked as one
+        let entities = entities::load_scene_entities(&scene)
+            .with_context(|| format!("Couldn't cook {}", scene.entity_path()))?;
+        bundle.insert(&scene.entity_path(), &entities)?;
+        bundle.cook(&scene.singleton_path(), |path| {
+            load_versioned_file::<SingletonDatabase>(path, SerializedFileKind::Singletons)
+        })?;
+        bundle.cook(
+            &scene.interaction_rules_path(),
+            load_serialized_file::<InteractionRules>,
+        )?;
+    }
+
+    for path in glob::glob(&format!("{}/**/*.prefab", PREFAB_DIRECTORY))? {
+        bundle.cook(path?.to_str().unwrap(), |path| {
+            load_versioned_file::<Prefab>(path, SerializedFileKind::Prefab)
+        })?;
+    }
+    bundle.cook(
+        &format!("{}/{}", PREFAB_DIRECTORY, DEFAULT_SINGLETONS_SUBPATH),
+        |path| load_versioned_file::<SingletonDatabase>(path, SerializedFileKind::Singletons),
+    )?;
+    bundle.cook(
+        &format!("{}/{}", PREFAB_DIRECTORY, INTERACTION_RULES_SUBPATH),
+        load_serialized_file::<InteractionRules>,
+    )?;
+
+    bundle.cook(SPRITE_PATH, load_serialized_file::<Vec<SpriteInGameData>>)?;
+    bundle.cook(SPRITESHEET_PATH, load_serialized_file::<SpriteSheet>)?;
+    bundle.cook(TILESET_PATH, load_serialized_file::<Vec<TileSetSerialized>>)?;
+
+    safe_files::write_atomically(COOKED_BUNDLE_PATH, &bincode::serialize(&bundle)?)?;
+
+    Ok(bundle)
+}
+
+impl CookedBundle {
+    fn cook<T: serde::Serialize>(
+        &mut self,
+        path: &str,
+        load: impl FnOnce(&str) -> Result<T, Error>,
+    ) -> Result<(), Error> {
+        if Path::new(path).exists() == false {
+            return Ok(());
+        }
+
+        let item = load(path).with_context(|| format!("Couldn't cook {}", path))?;
+        self.insert(path, &item)
+    }
+
+    fn insert<T: serde::Serialize>(&mut self, path: &str, item: &T) -> Result<(), Error> {
+        self.files.insert(path.to_string(), bincode::serialize(item)?);
+
+        Ok(())
+    }
+}
@@ -1,35 +1,119 @@
 use super::{
     imgui_component_utils::{EntitySerializationCommand, EntitySerializationCommandType},
+    migrations::SerializedFileKind,
     *,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 use uuid::Uuid;
 
-pub fn path() -> (String, bool) {
-    let scene: &Scene = &scene_system::CURRENT_SCENE.lock().unwrap();
-    (scene.entity_path(), scene.is_prefab())
+fn current_scene() -> Scene {
+    scene_system::CURRENT_SCENE.lock().unwrap().clone()
 }
 
-pub fn load_all_entities() -> Result<HashMap<Uuid, SerializedEntity>, Error> {
-    let (scene_entity_path, is_prefab) = path();
-    if is_prefab {
-        let prefab: Prefab = load_serialized_file(&scene_entity_path)?;
-        Ok(prefab.members)
+/// The directory holding the Scene's entities, one file each, if that's how they're
+/// stored. Prefabs, cooked Scenes and Scenes which haven't been split up yet are
+/// all one file, which is saved whole.
+fn entity_directory(scene: &Scene) -> Option<String> {
+    let entity_path = scene.entity_path();
+    if scene.is_prefab() || cooked::is_cooked(&entity_path) || Path::new(&entity_path).exists() {
+        None
     } else {
-        load_serialized_file(&scene_entity_path)
+        Some(scene.entity_directory())
+    }
+}
+
+fn entity_file_path(entity_directory: &str, id: &Uuid) -> String {
+    format!("{}/{}.yaml", entity_directory, id)
+}
+
+// Every entity file in the directory, along with the id it's named after.
+fn entity_files(entity_directory: &str) -> Result<Vec<(Uuid, String)>, Error> {
+    if Path::new(entity_directory).is_dir() == false {
+        return Ok(vec![]);
+    }
+
+    let mut entity_files = vec![];
+    for entry in fs::read_dir(entity_directory)? {
+        let path = entry?.path();
+        if path.extension().map_or(true, |extension| extension != "yaml") {
+            continue;
+        }
+
+        let id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| Uuid::parse_str(stem).ok());
+        if let Some(id) = id {
+            entity_files.push((id, path.to_string_lossy().to_string()));
+        }
     }
+
+    Ok(entity_files)
+}
+
+pub fn load_all_entities() -> Result<HashMap<Uuid, SerializedEntity>, Error> {
+    load_scene_entities(&current_scene())
 }
 
+pub fn load_scene_entities(scene: &Scene) -> Result<HashMap<Uuid, SerializedEntity>, Error> {
+    if scene.is_prefab() {
+        let prefab: Prefab = load_versioned_file(&scene.entity_path(), SerializedFileKind::Prefab)?;
+        return Ok(prefab.members);
+    }
+
+    let entity_directory = match entity_directory(scene) {
+        Some(entity_directory) => entity_directory,
+        None => return load_versioned_file(&scene.entity_path(), SerializedFileKind::Entities),
+    };
+
+    let mut entities = HashMap::new();
+    for (_, path) in entity_files(&entity_directory)? {
+        match load_versioned_file::<SerializedEntity>(&path, SerializedFileKind::Entity) {
+            Ok(serialized_entity) => {
+                entities.insert(serialized_entity.id, serialized_entity);
+            }
+            Err(e) if recovery_mode() => {
+                // Moving it out of the way means the next save won't remove it
+                let broken_path = format!("{}.broken", path);
+                fs::rename(&path, &broken_path)?;
+                error!(
+                    "Recovered {}, leaving out a broken entity. It's in {}. {}",
+                    entity_directory, broken_path, e
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(entities)
+}
+
+/// Saves every entity in the Scene, and removes any which aren't in `entities`.
+/// Entities which haven't changed aren't written at all. A Scene which was all
+/// in one file is split up into a file per entity.
 pub fn commit_all_entities(entities: &HashMap<Uuid, SerializedEntity>) -> AnyResult<()> {
-    let (path, is_prefab) = path();
-    if is_prefab {
-        let mut prefab: Prefab = load_serialized_file(&path)?;
+    let scene = current_scene();
+    let entity_path = scene.entity_path();
+    if scene.is_prefab() {
+        let mut prefab: Prefab = load_versioned_file(&entity_path, SerializedFileKind::Prefab)?;
         prefab.members = entities.clone();
 
-        save_serialized_file(&prefab, &path)
-    } else {
-        save_serialized_file(entities, &path)
+        return save_versioned_file(&prefab, &entity_path);
     }
+
+    let entity_directory = scene.entity_directory();
+    for (id, serialized_entity) in entities.iter() {
+        save_versioned_file(serialized_entity, &entity_file_path(&entity_directory, id))?;
+    }
+
+    for (id, path) in entity_files(&entity_directory)? {
+        if entities.contains_key(&id) == false {
+            remove_serialized_file(&path)?;
+        }
+    }
+
+    // This goes last, since it's what we load from until it's gone
+    remove_serialized_file(&entity_path)
 }
 
 pub fn process_serialized_command(
@@ -155,6 +239,16 @@ pub fn serialize_entity_full(
 
 // @techdebt Use it or lose it!
 pub fn unserialize_entity(serialized_id: &uuid::Uuid) -> Result<bool, Error> {
+    if let Some(entity_directory) = entity_directory(&current_scene()) {
+        let path = entity_file_path(&entity_directory, serialized_id);
+        if Path::new(&path).exists() == false {
+            return Ok(false);
+        }
+
+        remove_serialized_file(&path)?;
+        return Ok(true);
+    }
+
     let mut entities = load_all_entities()?;
 
     // FIND THE OLD PREFAB
@@ -164,7 +258,16 @@ pub fn unserialize_entity(serialized_id: &uuid::Uuid) -> Result<bool, Error> {
     Ok(succeeded)
 }
 
+/// Saves a single entity. In a Scene with a file per entity, that's the only file
+/// we write to.
 pub fn commit_entity_to_serialized_scene(serialized_entity: SerializedEntity) -> Result<(), Error> {
+    if let Some(entity_directory) = entity_directory(&current_scene()) {
+        return save_versioned_file(
+            &serialized_entity,
+            &entity_file_path(&entity_directory, &serialized_entity.id),
+        );
+    }
+
     let mut entities = load_all_entities()?;
     entities.insert(serialized_entity.id, serialized_entity);
 
@@ -178,6 +281,15 @@ pub fn load_committed_entity(
 }
 
 pub fn load_entity_by_id(id: &uuid::Uuid) -> Result<Option<SerializedEntity>, Error> {
+    if let Some(entity_directory) = entity_directory(&current_scene()) {
+        let path = entity_file_path(&entity_directory, id);
+        if Path::new(&path).exists() == false {
+            return Ok(None);
+        }
+
+        return Ok(Some(load_versioned_file(&path, SerializedFileKind::Entity)?));
+    }
+
     let mut entities: HashMap<Uuid, SerializedEntity> = load_all_entities()?;
     Ok(entities.remove(id))
 }
@@ -0,0 +1,15 @@
+const DEV_INPUT_BINDINGS_PATH: &str = "dev/input_bindings.yaml";
+use crate::resources::InputBindings;
+use anyhow::Result;
+
+pub fn load_input_bindings() -> Result<InputBindings> {
+    let mut input_bindings: InputBindings = super::load_serialized_file(DEV_INPUT_BINDINGS_PATH)?;
+    input_bindings.remove_invalid_bindings();
+
+    Ok(input_bindings)
+}
+
+pub fn serialize_input_bindings(input_bindings: &InputBindings) -> Result<()> {
+    super::save_serialized_file(input_bindings, DEV_INPUT_BINDINGS_PATH)?;
+    Ok(())
+}
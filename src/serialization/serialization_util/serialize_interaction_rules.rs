@@ -0,0 +1,16 @@
+use super::*;
+
+pub fn path() -> String {
+    scene_system::CURRENT_SCENE
+        .lock()
+        .unwrap()
+        .interaction_rules_path()
+}
+
+pub fn load_interaction_rules() -> Result<InteractionRules, Error> {
+    load_serialized_file(&path())
+}
+
+pub fn serialize_interaction_rules(interaction_rules: &InteractionRules) -> Result<(), Error> {
+    save_serialized_file(interaction_rules, &path())
+}
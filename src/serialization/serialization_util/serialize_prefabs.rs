@@ -1,4 +1,4 @@
-use super::*;
+use super::{migrations::SerializedFileKind, *};
 use uuid::Uuid;
 
 lazy_static::lazy_static! {
@@ -26,19 +26,20 @@ pub fn cycle_prefab(prefab: Prefab) -> Result<Prefab, Error> {
 pub fn serialize_prefab(prefab: &Prefab) -> Result<(), Error> {
     let path = path(&prefab.root_id().to_string());
 
-    save_serialized_file(&prefab, &path)
+    save_versioned_file(&prefab, &path)
 }
 
 pub fn invalidate_prefab(prefab: &Prefab) -> AnyResult<()> {
     let path = path(&prefab.root_id().to_string());
-    fs::remove_file(&path)?;
+    remove_serialized_file(&path)?;
 
-    save_serialized_file(prefab, &invalid_path(&prefab.root_id().to_string()))
+    save_versioned_file(prefab, &invalid_path(&prefab.root_id().to_string()))
 }
 
 pub fn load_prefab(prefab_id: &Uuid) -> Result<Option<Prefab>, Error> {
     // ENTITIES
-    let prefab: Result<Prefab, _> = load_serialized_file(&path(&prefab_id.to_string()));
+    let prefab: Result<Prefab, _> =
+        load_versioned_file(&path(&prefab_id.to_string()), SerializedFileKind::Prefab);
 
     Ok(prefab
         .map_err(|e| error!("Error loading Prefab File: {}", e))
@@ -52,9 +53,16 @@ pub fn load_prefab(prefab_id: &Uuid) -> Result<Option<Prefab>, Error> {
 pub fn load_all_prefabs() -> AnyResult<PrefabMap> {
     let mut ret = std::collections::HashMap::new();
 
-    for path in glob::glob(&PREFAB_GLOB)? {
-        let path = path?;
-        let prefab: Prefab = load_serialized_file(path.to_str().unwrap())?;
+    // A cooked bundle has every Prefab in it, and the YAML might not be here at all
+    let paths = match cooked::cooked_paths(PREFAB_DIRECTORY, ".prefab") {
+        Some(paths) => paths,
+        None => glob::glob(&PREFAB_GLOB)?
+            .map(|path| Ok(path?.to_str().unwrap().to_string()))
+            .collect::<Result<Vec<_>, Error>>()?,
+    };
+
+    for path in paths {
+        let prefab: Prefab = load_versioned_file(&path, SerializedFileKind::Prefab)?;
         ret.insert(prefab.root_id(), prefab);
     }
 
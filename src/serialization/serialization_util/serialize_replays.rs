@@ -0,0 +1,21 @@
+use super::*;
+use replay_system::Replay;
+
+/// Unlike most of our files, a missing Replay is an error rather than
+/// something to create -- there's nothing sensible to play back.
+pub fn load_replay(path: &str) -> Result<Replay, Error> {
+    if std::path::Path::new(path).exists() == false {
+        bail!("No Replay found at {}", path);
+    }
+
+    let file_string = fs::read_to_string(path)?;
+    Ok(serde_yaml::from_str(&file_string)?)
+}
+
+pub fn serialize_replay(replay: &Replay, path: &str) -> Result<(), Error> {
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    save_serialized_file(replay, path)
+}
@@ -1,13 +1,13 @@
-use super::*;
+use super::{migrations::SerializedFileKind, *};
 
 pub fn path() -> String {
     scene_system::CURRENT_SCENE.lock().unwrap().singleton_path()
 }
 
 pub fn load_singleton_database() -> Result<SingletonDatabase, Error> {
-    load_serialized_file(&path())
+    load_versioned_file(&path(), SerializedFileKind::Singletons)
 }
 
 pub fn serialize_singleton_database(singleton_database: &SingletonDatabase) -> Result<(), Error> {
-    save_serialized_file(singleton_database, &path())
+    save_versioned_file(singleton_database, &path())
 }
@@ -2,7 +2,8 @@ use super::sprite_resources::*;
 use super::*;
 use clockwork_build_shared::sprite_packing::shared::SpriteSheet;
 
-const SPRITE_PATH: &str = "assets/serialized_data/resources/sprite_data.yaml";
+pub(super) const SPRITE_PATH: &str = "assets/serialized_data/resources/sprite_data.yaml";
+pub(super) const SPRITESHEET_PATH: &str = "assets/gen/textures/packed_sheet_0.yaml";
 
 pub fn serialize_sprite(sprite_data: &SpriteData) -> Result<(), Error> {
     // LOAD EXISTING SPRITE DATA VEC
@@ -59,5 +60,5 @@ pub fn load_sprites() -> Result<Vec<SpriteInGameData>, Error> {
 }
 
 pub fn load_spritesheets() -> Result<SpriteSheet, Error> {
-    load_serialized_file("assets/gen/textures/packed_sheet_0.yaml")
+    load_serialized_file(SPRITESHEET_PATH)
 }
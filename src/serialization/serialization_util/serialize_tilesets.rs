@@ -1,6 +1,6 @@
 use super::{tile_resources::*, *};
 
-const TILESET_PATH: &str = "assets/serialized_data/resources/tileset_data.yaml";
+pub(super) const TILESET_PATH: &str = "assets/serialized_data/resources/tileset_data.yaml";
 
 pub fn serialize_tileset(new_tset: TileSet) -> Result<(), Error> {
     let mut serialized_tileset: Vec<TileSetSerialized> = load_serialized_tilesets()?;
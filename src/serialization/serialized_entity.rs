@@ -1,5 +1,5 @@
 use super::{
-    physics_components::*, prefab_system, ComponentBounds, ComponentDatabase, ConversantNPC, DrawRectangle,
+    physics_components, prefab_system, ComponentBounds, ComponentDatabase, ConversantNPC, DrawRectangle,
     Entity, Follow, GraphNode, GridObject, Marker, Name, NonInspectableEntities, Player, PrefabMarker,
     ResourcesDatabase, SceneSwitcher, SerializableComponent, SingletonDatabase, SoundSource, Sprite,
     TextSource, Transform, Velocity,
@@ -50,31 +50,77 @@ impl<T: Default> SerializedComponentExtenstions<T> for SerializedComponentWrappe
     }
 }
 
-// This should mirror ComponentDatabse
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
-#[serde(default)]
-pub struct SerializedEntity {
-    pub name: SerializedComponentWrapper<Name>,
-    pub player: SerializedComponentWrapper<Player>,
-    pub transform: SerializedComponentWrapper<Transform>,
-    pub grid_object: SerializedComponentWrapper<GridObject>,
-    pub scene_switcher: SerializedComponentWrapper<SceneSwitcher>,
-    pub graph_node: SerializedComponentWrapper<GraphNode>,
-    pub velocity: SerializedComponentWrapper<Velocity>,
-    pub sprite: SerializedComponentWrapper<Sprite>,
-    pub sound_source: SerializedComponentWrapper<SoundSource>,
-    pub draw_rectangle: SerializedComponentWrapper<DrawRectangle>,
-    pub bounding_box: SerializedComponentWrapper<BoundingBox>,
-    pub text_source: SerializedComponentWrapper<TextSource>,
-    // pub tilemap: SerializedComponentWrapper<TilemapSerialized>,
-    pub follow: SerializedComponentWrapper<Follow>,
-    pub conversant_npc: SerializedComponentWrapper<ConversantNPC>,
-    pub prefab_marker: SerializedComponentWrapper<PrefabMarker>,
+macro_rules! define_serialized_entity {
+    (
+        serialized {
+            $( $field:ident: $component:ty => $key:ident $( [$flag:ident] )? ),* $(,)?
+        }
+        unserialized {
+            $( $unserialized_field:ident: $unserialized_component:ty $( [$unserialized_flag:ident] )? ),* $(,)?
+        }
+    ) => {
+        // This mirrors ComponentDatabase
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+        #[serde(default)]
+        pub struct SerializedEntity {
+            $( pub $key: SerializedComponentWrapper<$component>, )*
+
+            pub id: Uuid,
+            pub marker: Option<Marker>,
+        }
+
+        impl SerializedEntity {
+            pub fn foreach_component(
+                &mut self,
+                entity_bitmask: NonInspectableEntities,
+                mut f: impl FnMut(&dyn ComponentBounds, &bool),
+                f_util: Option<impl FnMut(&mut Uuid, &mut Option<Marker>)>,
+            ) {
+                let SerializedEntity {
+                    $( $key, )*
+                    id,
+                    marker,
+                } = self;
+
+                $(
+                    if entity_bitmask.contains(NonInspectableEntities::empty() $( | NonInspectableEntities::$flag )?) {
+                        if let Some(serialized_component) = $key {
+                            f(&serialized_component.inner, &serialized_component.active);
+                        }
+                    }
+                )*
+
+                if let Some(mut f_util) = f_util {
+                    f_util(id, marker);
+                }
+            }
 
-    pub id: Uuid,
-    pub marker: Option<Marker>,
+            /// Removes every component for which `f` returns true. The PrefabMarker
+            /// is never removed, since it says which Prefab we're deduping against.
+            pub fn foreach_component_dedup(&mut self, mut f: impl FnMut(&dyn ComponentBounds, &bool) -> bool) {
+                let entity_bitmask = NonInspectableEntities::all() - NonInspectableEntities::PREFAB;
+                let SerializedEntity {
+                    $( $key, )*
+                    id: _,
+                    marker: _,
+                } = self;
+
+                $(
+                    if entity_bitmask.contains(NonInspectableEntities::empty() $( | NonInspectableEntities::$flag )?) {
+                        if let Some(serialized_component) = $key {
+                            if f(&serialized_component.inner, &serialized_component.active) {
+                                *$key = None;
+                            }
+                        }
+                    }
+                )*
+            }
+        }
+    };
 }
 
+crate::with_registered_components!(define_serialized_entity);
+
 impl SerializedEntity {
     pub fn new(
         entity_id: &Entity,
@@ -162,129 +208,6 @@ impl SerializedEntity {
         }
     }
 
-    pub fn foreach_component(
-        &mut self,
-        entity_bitmask: NonInspectableEntities,
-        mut f: impl FnMut(&dyn ComponentBounds, &bool),
-        f_util: Option<impl FnMut(&mut Uuid, &mut Option<Marker>)>,
-    ) {
-        let SerializedEntity {
-            name,
-            player,
-            transform,
-            grid_object,
-            scene_switcher,
-            graph_node,
-            velocity,
-            sprite,
-            sound_source,
-            draw_rectangle,
-            bounding_box,
-            text_source,
-            // tilemap,
-            follow,
-            conversant_npc,
-            prefab_marker,
-            id,
-            marker,
-        } = self;
-
-        macro_rules! do_action {
-            ( $( $x:ident ),* ) => {
-                $(
-                    if let Some(serialized_component) = $x {
-                        f(&serialized_component.inner, &serialized_component.active);
-                    }
-                )*
-            };
-        }
-
-        if entity_bitmask.contains(NonInspectableEntities::NAME) {
-            do_action!(name);
-        }
-
-        do_action!(
-            player,
-            transform,
-            grid_object,
-            scene_switcher,
-            velocity,
-            sprite,
-            sound_source,
-            draw_rectangle,
-            bounding_box,
-            text_source,
-            // tilemap,
-            follow,
-            conversant_npc
-        );
-
-        if entity_bitmask.contains(NonInspectableEntities::GRAPH_NODE) {
-            do_action!(graph_node);
-        }
-
-        if entity_bitmask.contains(NonInspectableEntities::PREFAB) {
-            do_action!(prefab_marker);
-        }
-
-        if let Some(mut f_util) = f_util {
-            f_util(id, marker);
-        }
-    }
-
-    pub fn foreach_component_dedup(&mut self, mut f: impl FnMut(&dyn ComponentBounds, &bool) -> bool) {
-        let SerializedEntity {
-            name,
-            player,
-            transform,
-            grid_object,
-            scene_switcher,
-            graph_node,
-            velocity,
-            sprite,
-            sound_source,
-            draw_rectangle,
-            bounding_box,
-            text_source,
-            // tilemap,
-            follow,
-            conversant_npc,
-            prefab_marker: _,
-            id: _,
-            marker: _,
-        } = self;
-
-        macro_rules! dedup_serialization {
-            ( $( $x:ident ),* ) => {
-                $(
-                    if let Some(serialized_component) = $x {
-                        if f(&serialized_component.inner, &serialized_component.active) {
-                            *$x = None;
-                        }
-                    }
-                )*
-            };
-        }
-
-        dedup_serialization!(
-            name,
-            player,
-            transform,
-            grid_object,
-            scene_switcher,
-            graph_node,
-            velocity,
-            sprite,
-            sound_source,
-            draw_rectangle,
-            bounding_box,
-            text_source,
-            // tilemap,
-            follow,
-            conversant_npc
-        );
-    }
-
     pub fn log_to_console(&self) {
         println!("---");
         println!("Serialized Entity: {:#?}", self);
@@ -1,16 +1,21 @@
 pub use super::*;
 
+pub mod conversant_npc_system;
 pub mod cross_cutting_system;
 pub mod follow_system;
 pub mod grid_system;
+pub mod history_system;
 pub mod imgui_system;
 pub mod input_system;
+pub mod level_system;
 pub mod physics_system;
 pub mod player_system;
 pub mod prefab_system;
 pub mod renderer_system;
+pub mod replay_system;
 pub mod scene_system;
 pub mod singleton_systems;
+pub mod solver_system;
 pub mod sound_system;
 pub mod sprite_system;
 pub mod tilemap_system;
@@ -0,0 +1,49 @@
+use super::{CommandBuffer, ComponentList, ConversantNPC, Join, Transform};
+
+/// Shows an NPC's Bang UI while its conversation partner is close enough, and takes
+/// it away once they walk off. The UI is made through `commands`, since we can't
+/// make Entities while we're iterating.
+pub fn update_conversant_npcs(
+    conversant_npcs: &ComponentList<ConversantNPC>,
+    transforms: &ComponentList<Transform>,
+    commands: &mut CommandBuffer,
+) {
+    for (entity, (conversant_npc, transform)) in (conversant_npcs, transforms).join() {
+        let position = transform.world_position();
+        let is_close = conversant_npc
+            .conversation_partner
+            .target
+            .and_then(|partner| transforms.get(&partner))
+            .map_or(false, |partner_transform| {
+                (partner_transform.inner().world_position() - position).magnitude_squared()
+                    <= conversant_npc.distance * conversant_npc.distance
+            });
+
+        match (is_close, conversant_npc.runtime_ui) {
+            (true, None) => {
+                if let Some(prefab_id) = conversant_npc.initial_ui_prefab.target {
+                    let runtime_ui = commands.instantiate_prefab(prefab_id);
+
+                    commands.edit_component::<Transform>(runtime_ui, move |transform, _| {
+                        transform.set_local_position(position);
+                    });
+                    commands.edit_component::<ConversantNPC>(
+                        entity,
+                        move |conversant_npc, pending_entities| {
+                            conversant_npc.runtime_ui = pending_entities.get(runtime_ui);
+                        },
+                    );
+                }
+            }
+
+            (false, Some(runtime_ui)) => {
+                commands.destroy_entity(runtime_ui);
+                commands.edit_component::<ConversantNPC>(entity, |conversant_npc, _| {
+                    conversant_npc.runtime_ui = None;
+                });
+            }
+
+            _ => {}
+        }
+    }
+}
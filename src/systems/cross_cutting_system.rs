@@ -1,5 +1,6 @@
 use super::{
-    physics_components::BoundingBox, ComponentList, DrawRectangle, Ecs, Rect, ResourcesDatabase, Sprite,
+    physics_components::BoundingBox, ComponentList, DrawRectangle, Ecs, Join, Maybe, Rect, ResourcesDatabase,
+    Sprite,
 };
 
 pub fn cross_cutting_system(ecs: &mut Ecs, resources: &ResourcesDatabase) {
@@ -20,13 +21,10 @@ fn bounding_box_and_sprite(
     sprites: &ComponentList<Sprite>,
     resources: &ResourcesDatabase,
 ) {
-    for this_bb in bbs.iter_mut() {
-        let this_entity_id = this_bb.entity_id();
-        let this_bounding_box: &mut BoundingBox = this_bb.inner_mut();
-
+    for (_, (this_bounding_box, this_sprite)) in (bbs, Maybe(sprites)).join() {
         if this_bounding_box.bind_to_sprite {
-            if let Some(this_sprite) = sprites.get(&this_entity_id) {
-                if let Some(sprite_name) = &this_sprite.inner().sprite_name {
+            if let Some(this_sprite) = this_sprite {
+                if let Some(sprite_name) = &this_sprite.sprite_name {
                     let sprite_data = resources.sprites.get(sprite_name).unwrap();
                     let rel_location = sprite_data.origin.sprite_location_relative(sprite_data.size);
 
@@ -43,15 +41,12 @@ fn draw_rectangle_and_bounding_box(
     draw_rects: &mut ComponentList<DrawRectangle>,
     bbs: &ComponentList<BoundingBox>,
 ) {
-    for this_draw_rect in draw_rects.iter_mut() {
-        let this_entity_id = this_draw_rect.entity_id();
-        let this_draw_rect_inner: &mut DrawRectangle = this_draw_rect.inner_mut();
-
-        if this_draw_rect_inner.bind_to_bounding_box {
-            if let Some(this_bb) = bbs.get(&this_entity_id) {
-                this_draw_rect_inner.rect = this_bb.inner().rect;
+    for (_, (this_draw_rect, this_bb)) in (draw_rects, Maybe(bbs)).join() {
+        if this_draw_rect.bind_to_bounding_box {
+            if let Some(this_bb) = this_bb {
+                this_draw_rect.rect = this_bb.rect;
             } else {
-                this_draw_rect_inner.bind_to_bounding_box = false;
+                this_draw_rect.bind_to_bounding_box = false;
             }
         }
     }
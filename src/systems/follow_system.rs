@@ -14,12 +14,12 @@ pub fn update_follows(
                 let target_position = target_position + follow_c.inner().offset;
 
                 // Transform Components
-                let our_transform_c = transforms.get_mut_or_default(&follow_c.entity_id());
-                let transform_pos: &mut Vec2 = &mut our_transform_c.inner_mut().world_position();
+                let our_transform = transforms.get_mut_or_default(&follow_c.entity_id()).inner_mut();
+                let mut transform_pos: Vec2 = our_transform.world_position();
 
                 match follow_c.inner().approach {
                     Approach::Instant => {
-                        *transform_pos = target_position;
+                        transform_pos = target_position;
                     }
 
                     Approach::Linear(speed) => {
@@ -28,12 +28,16 @@ pub fn update_follows(
 
                     Approach::Asymptotic(weight) => {
                         transform_pos.asymptotic_moved(target_position, weight * delta_time);
-                        if (*transform_pos - target_position).magnitude_squared() < delta_time {
-                            *transform_pos = target_position;
+                        if (transform_pos - target_position).magnitude_squared() < delta_time {
+                            transform_pos = target_position;
                         }
                     }
                 };
 
+                // We can only set our local position, which moves us the same amount
+                let moved_by = transform_pos - our_transform.world_position();
+                our_transform.edit_local_position(|local_position| local_position + moved_by);
+
             // Move
             } else {
                 error_once!(
@@ -1,33 +1,180 @@
 use super::{
-    cardinals::CardinalPrime, scene_system, Component, ComponentList, Ecs, Entity, GridObject, GridType,
-    Marker, Name, Transform, Vec2,
+    cardinals::CardinalPrime,
+    history_system::{GridChange, MoveHistory, Turn},
+    scene_system, ComponentList, Ecs, Entity, GridInfo, GridType, Interaction, InteractionRules, Join,
+    Marker, Maybe, Name, SceneSwitcher, Sprite, Transform, Vec2,
 };
 use array2d::Array2D;
 
-pub const GRID_DIMENSIONS: (usize, usize) = (5, 10);
-pub const GRID_DIMENSIONS_MIN_F32: (f32, f32) = (8.0, 8.0);
-pub const GRID_DIMENSIONS_MAX_F32: (f32, f32) =
-    (GRID_DIMENSIONS.0 as f32 * 8.0, GRID_DIMENSIONS.1 as f32 * 8.0);
-pub type Grid = Array2D<Option<Entity>>;
+/// The occupants of a single cell, in the order they arrived. A floor tile, a
+/// Flag and a Player can all share one cell.
+pub type GridCell = Vec<Entity>;
 
-pub fn update_grid_positions(ecs: &mut Ecs, grid: &mut Grid) {
-    // ImGui Movement
-    for grid_object_c in ecs.component_database.grid_objects.iter_mut() {
-        let id = grid_object_c.entity_id();
-        let grid_object: &mut GridObject = grid_object_c.inner_mut();
+/// Raised by an `Interaction::TriggerEvent`. Events only live until the
+/// start of the next update, so read them right after `update_grid_positions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridEvent {
+    pub name: String,
+    pub mover: Entity,
+    pub occupant: Entity,
+}
+
+/// A change to the Grid made by `attempt_to_move`. These are board-level only;
+/// `update_grid_positions` turns them into changes to Transforms and Sprites.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardEffect {
+    Moved {
+        entity: Entity,
+        from: (usize, usize),
+        to: (usize, usize),
+        from_index: Option<usize>,
+    },
+    Destroyed {
+        entity: Entity,
+        position: (usize, usize),
+        index: Option<usize>,
+    },
+    SwitchScene {
+        mover: Entity,
+        occupant: Entity,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Grid {
+    cells: Array2D<GridCell>,
+    info: GridInfo,
+    rules: InteractionRules,
+    events: Vec<GridEvent>,
+}
+
+impl Grid {
+    pub fn new(info: GridInfo, rules: InteractionRules) -> Self {
+        Self {
+            cells: Array2D::filled_with(GridCell::new(), info.width, info.height),
+            info,
+            rules,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn info(&self) -> &GridInfo {
+        &self.info
+    }
+
+    pub fn rules(&self) -> &InteractionRules {
+        &self.rules
+    }
+
+    pub fn events(&self) -> &[GridEvent] {
+        &self.events
+    }
+
+    pub fn clear_events(&mut self) {
+        self.events.clear();
+    }
+
+    /// Every position on the Grid, column by column.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let height = self.info.height;
+        (0..self.info.width).flat_map(move |x| (0..height).map(move |y| (x, y)))
+    }
+
+    pub fn occupants(&self, pos: (usize, usize)) -> &[Entity] {
+        &self.cells[pos]
+    }
+
+    /// Places an Entity on top of the cell, unless it is already there.
+    pub fn insert(&mut self, pos: (usize, usize), entity: Entity) {
+        let cell = &mut self.cells[pos];
+        if cell.contains(&entity) == false {
+            cell.push(entity);
+        }
+    }
+
+    /// Places an Entity at a given depth of the cell. This is used to put
+    /// the cell back exactly as it was when undoing.
+    pub fn insert_at(&mut self, pos: (usize, usize), index: usize, entity: Entity) {
+        let cell = &mut self.cells[pos];
+        if cell.contains(&entity) == false {
+            let index = index.min(cell.len());
+            cell.insert(index, entity);
+        }
+    }
+
+    /// Removes an Entity from the cell, returning the depth it was at.
+    pub fn remove(&mut self, pos: (usize, usize), entity: Entity) -> Option<usize> {
+        let cell = &mut self.cells[pos];
+        let index = cell.iter().position(|e| *e == entity)?;
+        cell.remove(index);
+
+        Some(index)
+    }
 
+    /// Moves an Entity from one cell to the top of another.
+    pub fn move_entity(&mut self, entity: Entity, from: (usize, usize), to: (usize, usize)) -> BoardEffect {
+        let from_index = self.remove(from, entity);
+        self.insert(to, entity);
+
+        BoardEffect::Moved {
+            entity,
+            from,
+            to,
+            from_index,
+        }
+    }
+
+    /// A copy of every cell, in the order of `positions`. Two Grids with
+    /// the same snapshot have the same board.
+    pub fn snapshot(&self) -> Vec<GridCell> {
+        self.positions().map(|pos| self.cells[pos].clone()).collect()
+    }
+
+    /// Returns the first cell an Entity is in. This walks the entire Grid.
+    pub fn find(&self, entity: Entity) -> Option<(usize, usize)> {
+        self.positions().find(|pos| self.cells[*pos].contains(&entity))
+    }
+
+    /// Removes an Entity from every cell it is in. This walks the entire Grid,
+    /// so it should only be used by editor code.
+    pub fn remove_everywhere(&mut self, entity: Entity) {
+        for x in 0..self.info.width {
+            for y in 0..self.info.height {
+                self.remove((x, y), entity);
+            }
+        }
+    }
+}
+
+/// Returns true if anything moved on the Grid this update.
+pub fn update_grid_positions(ecs: &mut Ecs, grid: &mut Grid, move_history: &mut MoveHistory) -> bool {
+    grid.clear_events();
+
+    // ImGui Movement
+    let cd = &mut ecs.component_database;
+    for (id, (grid_object, mut transform)) in (&mut cd.grid_objects, Maybe(&mut cd.transforms)).join() {
         if grid_object.move_to_point {
-            if let Some(transform) = ecs.component_database.transforms.get_mut(&id) {
-                let current_position = world_to_grid_position(transform.inner().world_position());
+            if let Some(transform) = &mut transform {
+                let current_position = grid.info().world_to_grid_position(transform.world_position());
                 let desired_position: (usize, usize) = (
                     grid_object.move_to_point_pos.x as usize,
                     grid_object.move_to_point_pos.y as usize,
                 );
 
-                if desired_position.0 >= GRID_DIMENSIONS.0 || desired_position.1 >= GRID_DIMENSIONS.1 {
+                if grid.info().contains(desired_position) == false {
                     error!("Couldn't move! Attempting to move to far!")
                 } else {
-                    move_entity(transform, grid, desired_position, current_position);
+                    // If we were off the Grid, there's nothing to move us out of
+                    match current_position {
+                        Some(current_position) => {
+                            grid.move_entity(id, current_position, desired_position);
+                        }
+                        None => grid.insert(desired_position, id),
+                    }
+                    transform.set_local_position(grid.info().grid_to_world_position(desired_position));
+
+                    // The board was edited outside of a Turn, so our History can't be trusted.
+                    move_history.clear();
                 }
             }
 
@@ -37,8 +184,9 @@ pub fn update_grid_positions(ecs: &mut Ecs, grid: &mut Grid) {
         }
 
         if grid_object.register {
-            if let Some(transform) = ecs.component_database.transforms.get(&id) {
-                register_entity(grid, id, transform.inner().world_position(), None);
+            if let Some(transform) = &transform {
+                grid.remove_everywhere(id);
+                register_entity(grid, id, transform.world_position(), None);
             }
 
             grid_object.register = false;
@@ -47,43 +195,47 @@ pub fn update_grid_positions(ecs: &mut Ecs, grid: &mut Grid) {
 
     // Player Movement
     let mut attempted_moves = vec![];
-    for player in ecs.component_database.players.iter_mut() {
-        let entity_id = player.entity_id();
-
-        let movement: Option<CardinalPrime> = {
-            if let Some(vc) = ecs.component_database.velocities.get_mut(&entity_id) {
-                vc.inner_mut().intended_direction.take()
-            } else {
-                None
-            }
-        };
-        let current_position = ecs
-            .component_database
-            .transforms
-            .get_mut(&entity_id)
-            .map(|tc| world_to_grid_position(tc.inner_mut().world_position()));
-
-        if let Some(movement) = movement {
-            if let Some(current_position) = current_position {
+    for (entity_id, (_, velocity, transform)) in (&cd.players, &mut cd.velocities, &cd.transforms).join() {
+        if let Some(movement) = velocity.intended_direction.take() {
+            if let Some(current_position) = grid.info().world_to_grid_position(transform.world_position()) {
                 attempted_moves.push((entity_id, movement, current_position));
             }
         }
     }
 
     // We do this so we don't reallocate under ourselves!
+    let mut effects = vec![];
     for (entity_id, movement, current_position) in attempted_moves {
-        if let Some(valid_next_position) = move_position(current_position, movement) {
+        if let Some(valid_next_position) = grid.info().move_position(current_position, movement) {
+            let grid_objects = &ecs.component_database.grid_objects;
             attempt_to_move(
                 &entity_id,
                 GridType::Player,
                 current_position,
                 valid_next_position,
                 movement,
-                ecs,
                 grid,
+                &|entity: &Entity| grid_objects.get(entity).map(|g| g.inner().grid_type()),
+                &mut effects,
             );
         }
     }
+
+    let mut turn = Turn::default();
+    let cd = &mut ecs.component_database;
+    apply_effects(
+        grid,
+        effects,
+        &mut cd.transforms,
+        &mut cd.sprites,
+        &cd.scene_switchers,
+        &mut turn,
+    );
+
+    let turn_taken = turn.is_empty() == false;
+    move_history.push_turn(turn);
+
+    turn_taken
 }
 
 pub fn initialize_transforms(
@@ -108,139 +260,264 @@ pub fn initialize_transforms(
     }
 }
 
-fn attempt_to_move(
+/// Moves an Entity on the Grid, following the Grid's InteractionRules. This only
+/// touches the Grid itself, so it can be run without an Ecs (see `solver_system`);
+/// what happened is written into `effects` for the caller to apply.
+pub fn attempt_to_move(
     entity_id: &Entity,
     my_object_type: GridType,
     current_position: (usize, usize),
     new_position: (usize, usize),
     movement: CardinalPrime,
-    ecs: &mut Ecs,
     grid: &mut Grid,
+    grid_type_of: &impl Fn(&Entity) -> Option<GridType>,
+    effects: &mut Vec<BoardEffect>,
 ) -> bool {
-    let mut move_to_spot = true;
-
-    // Check the Entity
-    if let Some(entity_in_grid) = grid[new_position] {
-        let grid_type = ecs
-            .component_database
-            .grid_objects
-            .get(&entity_in_grid)
-            .unwrap()
-            .inner()
-            .grid_type();
-
-        match grid_type {
-            GridType::Flag => {
-                if my_object_type == GridType::Player {
-                    if let Some(scene_switcher) = ecs.component_database.scene_switchers.get(&entity_in_grid)
-                    {
-                        if scene_system::set_next_scene(super::Scene::new(
-                            scene_switcher.inner().target_scene.clone(),
-                        )) == false
-                        {
-                            error!("Couldn't switch scenes! Does it exist?");
-                        };
-                    } else {
-                        error!("Flag wasn't a scene switcher? This is chaos!");
-                    }
-                }
-            }
-            GridType::Pushable => {
-                if let Some(next_next_position) = move_position(new_position, movement) {
-                    move_to_spot = attempt_to_move(
-                        &entity_in_grid,
-                        grid_type,
+    // We clone here, since pushing can change what's in the cell under us.
+    let occupants: Vec<(Entity, GridType, Interaction)> = grid
+        .occupants(new_position)
+        .iter()
+        .filter_map(|entity_in_grid| {
+            grid_type_of(entity_in_grid).map(|grid_type| {
+                let interaction = grid.rules().interaction(my_object_type, grid_type).clone();
+
+                (*entity_in_grid, grid_type, interaction)
+            })
+        })
+        .collect();
+
+    // Blockers go first, so we never push something and then find we can't move
+    if occupants
+        .iter()
+        .any(|(_, _, interaction)| *interaction == Interaction::Block)
+    {
+        return false;
+    }
+
+    // If a push fails partway, we put back everything this move had already done
+    let effects_before = effects.len();
+    let events_before = grid.events.len();
+
+    // Interactions which happen before we move...
+    for (entity_in_grid, grid_type, interaction) in occupants.iter() {
+        match interaction {
+            Interaction::Push => {
+                let pushed = match grid.info().move_position(new_position, movement) {
+                    Some(next_next_position) => attempt_to_move(
+                        entity_in_grid,
+                        *grid_type,
                         new_position,
                         next_next_position,
                         movement,
-                        ecs,
                         grid,
-                    );
+                        grid_type_of,
+                        effects,
+                    ),
+                    None => false,
+                };
+
+                if pushed == false {
+                    roll_back(grid, effects, effects_before, events_before);
+                    return false;
                 }
             }
-            GridType::Blockable | GridType::Player => {
-                move_to_spot = false;
-            }
-            GridType::NonInteractable => {
-                // good to go!
+
+            Interaction::Swap => {
+                effects.push(grid.move_entity(*entity_in_grid, new_position, current_position));
             }
+
+            _ => {}
         }
     }
 
-    if move_to_spot {
-        move_entity(
-            ecs.component_database.transforms.get_mut(entity_id).unwrap(),
-            grid,
-            new_position,
-            current_position,
-        );
-    }
+    effects.push(grid.move_entity(*entity_id, current_position, new_position));
 
-    move_to_spot
-}
+    // ...and those which happen once we've arrived
+    for (entity_in_grid, _, interaction) in occupants.iter() {
+        match interaction {
+            Interaction::Destroy => {
+                effects.push(BoardEffect::Destroyed {
+                    entity: *entity_in_grid,
+                    position: new_position,
+                    index: grid.remove(new_position, *entity_in_grid),
+                });
+            }
 
-fn register_entity(grid: &mut Grid, entity: Entity, position: Vec2, _names: Option<&ComponentList<Name>>) {
-    if position.x >= GRID_DIMENSIONS_MIN_F32.0
-        && position.x <= GRID_DIMENSIONS_MAX_F32.0
-        && position.y >= GRID_DIMENSIONS_MIN_F32.0
-        && position.y <= GRID_DIMENSIONS_MAX_F32.1
-    {
-        grid[world_to_grid_position(position)] = Some(entity);
-    }
-}
+            Interaction::SwitchScene => {
+                effects.push(BoardEffect::SwitchScene {
+                    mover: *entity_id,
+                    occupant: *entity_in_grid,
+                });
+            }
 
-fn move_entity(
-    transform: &mut Component<Transform>,
-    grid: &mut Grid,
-    valid_next_position: (usize, usize),
-    current_position: (usize, usize),
-) {
-    grid[valid_next_position] = Some(transform.entity_id());
-    grid[current_position] = None;
+            Interaction::TriggerEvent(name) => {
+                grid.events.push(GridEvent {
+                    name: name.clone(),
+                    mover: *entity_id,
+                    occupant: *entity_in_grid,
+                });
+            }
 
-    transform
-        .inner_mut()
-        .set_local_position(grid_to_world_position(valid_next_position));
-}
+            Interaction::Pass | Interaction::Block | Interaction::Push | Interaction::Swap => {}
+        }
+    }
 
-fn world_to_grid_position(pos: Vec2) -> (usize, usize) {
-    let tuple: (f32, f32) = ((pos - Vec2::new(8.0, 8.0)).cwise_div(Vec2::new(8.0, 8.0))).into();
-    (tuple.0 as usize, tuple.1 as usize)
+    true
 }
 
-fn grid_to_world_position(pos: (usize, usize)) -> Vec2 {
-    Vec2::new(pos.0 as f32, pos.1 as f32).cwise_product(Vec2::with_single(8.0)) + Vec2::with_single(8.0)
-}
+/// Undoes every BoardEffect after `effects_before` on the Grid, newest first,
+/// and forgets them along with any GridEvents they raised.
+fn roll_back(grid: &mut Grid, effects: &mut Vec<BoardEffect>, effects_before: usize, events_before: usize) {
+    for effect in effects.drain(effects_before..).rev() {
+        match effect {
+            BoardEffect::Moved {
+                entity,
+                from,
+                to,
+                from_index,
+            } => {
+                grid.remove(to, entity);
+                if let Some(from_index) = from_index {
+                    grid.insert_at(from, from_index, entity);
+                }
+            }
 
-fn move_position(pos: (usize, usize), direction: CardinalPrime) -> Option<(usize, usize)> {
-    match direction {
-        CardinalPrime::Right => {
-            if pos.0 + 1 < GRID_DIMENSIONS.0 {
-                Some((pos.0 + 1, pos.1))
-            } else {
-                None
+            BoardEffect::Destroyed {
+                entity,
+                position,
+                index,
+            } => {
+                if let Some(index) = index {
+                    grid.insert_at(position, index, entity);
+                }
             }
+
+            BoardEffect::SwitchScene { .. } => {}
         }
-        CardinalPrime::Up => {
-            if pos.1 + 1 < GRID_DIMENSIONS.1 {
-                Some((pos.0, pos.1 + 1))
-            } else {
-                None
+    }
+
+    grid.events.truncate(events_before);
+}
+
+/// Applies what `attempt_to_move` did on the Grid to the Transforms and Sprites,
+/// recording each change into the Turn so that it can be undone.
+pub fn apply_effects(
+    grid: &Grid,
+    effects: Vec<BoardEffect>,
+    transforms: &mut ComponentList<Transform>,
+    sprites: &mut ComponentList<Sprite>,
+    scene_switchers: &ComponentList<SceneSwitcher>,
+    turn: &mut Turn,
+) {
+    for effect in effects {
+        match effect {
+            BoardEffect::Moved {
+                entity,
+                from,
+                to,
+                from_index,
+            } => {
+                if let Some(transform) = transforms.get_mut(&entity) {
+                    turn.record(GridChange::Moved {
+                        entity,
+                        from,
+                        to,
+                        from_index,
+                        previous_local_position: transform.inner().local_position(),
+                    });
+
+                    transform
+                        .inner_mut()
+                        .set_local_position(grid.info().grid_to_world_position(to));
+                } else {
+                    error!("Entity {} moved on the Grid, but has no Transform!", entity);
+                }
             }
-        }
-        CardinalPrime::Left => {
-            if pos.0 != 0 {
-                Some((pos.0 - 1, pos.1))
-            } else {
-                None
+
+            BoardEffect::Destroyed {
+                entity,
+                position,
+                index,
+            } => {
+                let sprite_was_active = match sprites.get_mut(&entity) {
+                    Some(sprite) => {
+                        let was_active = sprite.is_active;
+                        sprite.is_active = false;
+                        was_active
+                    }
+                    None => false,
+                };
+
+                turn.record(GridChange::Destroyed {
+                    entity,
+                    position,
+                    index,
+                    sprite_was_active,
+                });
             }
-        }
-        CardinalPrime::Down => {
-            if pos.1 != 0 {
-                Some((pos.0, pos.1 - 1))
-            } else {
-                None
+
+            BoardEffect::SwitchScene { occupant, .. } => {
+                if let Some(scene_switcher) = scene_switchers.get(&occupant) {
+                    if scene_system::set_next_scene(super::Scene::new(
+                        scene_switcher.inner().target_scene.clone(),
+                    )) == false
+                    {
+                        error!("Couldn't switch scenes! Does it exist?");
+                    };
+                } else {
+                    error!("Flag wasn't a scene switcher? This is chaos!");
+                }
             }
         }
     }
 }
+
+fn register_entity(grid: &mut Grid, entity: Entity, position: Vec2, _names: Option<&ComponentList<Name>>) {
+    if let Some(grid_position) = grid.info().world_to_grid_position(position) {
+        grid.insert(grid_position, entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::EntityAllocator;
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn failed_push_leaves_the_board_untouched() {
+        let mut grid = Grid::new(GridInfo::default(), InteractionRules::default());
+        let mut entity_allocator = EntityAllocator::new();
+        let mut grid_types = HashMap::new();
+
+        let mut spawn = |grid: &mut Grid, grid_type: GridType, position: (usize, usize)| {
+            let entity = entity_allocator.allocate();
+            grid.insert(position, entity);
+            grid_types.insert(entity, grid_type);
+            entity
+        };
+
+        // Two Pushables share a cell, and there's only room to push one of them
+        let player = spawn(&mut grid, GridType::Player, (0, 0));
+        spawn(&mut grid, GridType::Pushable, (1, 0));
+        spawn(&mut grid, GridType::Pushable, (1, 0));
+        spawn(&mut grid, GridType::Blockable, (3, 0));
+        let before = grid.snapshot();
+
+        let mut effects = vec![];
+        let moved = attempt_to_move(
+            &player,
+            GridType::Player,
+            (0, 0),
+            (1, 0),
+            CardinalPrime::Right,
+            &mut grid,
+            &|entity: &Entity| grid_types.get(entity).cloned(),
+            &mut effects,
+        );
+
+        assert!(moved == false);
+        assert!(effects.is_empty());
+        assert_eq!(grid.snapshot(), before);
+    }
+}
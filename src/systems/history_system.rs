@@ -0,0 +1,275 @@
+use super::{grid_system::Grid, ComponentList, Entity, Sprite, Transform, Vec2};
+
+/// A single change to the board. We keep where in its old cell the Entity
+/// was, so that undoing puts the board back exactly as it was.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridChange {
+    Moved {
+        entity: Entity,
+        from: (usize, usize),
+        to: (usize, usize),
+        /// None if the Entity wasn't registered in its old cell at all.
+        from_index: Option<usize>,
+        previous_local_position: Vec2,
+    },
+    Destroyed {
+        entity: Entity,
+        position: (usize, usize),
+        index: Option<usize>,
+        sprite_was_active: bool,
+    },
+}
+
+/// Every GridChange which happened during one update, in the order that
+/// they happened. A Player pushing a chain of Pushables is one Turn.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Turn {
+    changes: Vec<GridChange>,
+}
+
+impl Turn {
+    pub fn record(&mut self, grid_change: GridChange) {
+        self.changes.push(grid_change);
+    }
+
+    pub fn changes(&self) -> &[GridChange] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MoveHistory {
+    turns: Vec<Turn>,
+}
+
+impl MoveHistory {
+    pub fn new() -> Self {
+        Self { turns: Vec::new() }
+    }
+
+    /// Adds a Turn to the History. Empty Turns are ignored, so that
+    /// undoing always rewinds something the player can see.
+    pub fn push_turn(&mut self, turn: Turn) {
+        if turn.is_empty() == false {
+            self.turns.push(turn);
+        }
+    }
+
+    pub fn turn_count(&self) -> usize {
+        self.turns.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.turns.clear();
+    }
+
+    /// Rewinds the last Turn, restoring the Grid and the Transforms and Sprites
+    /// which were changed. Returns false if there was nothing to undo.
+    pub fn undo(
+        &mut self,
+        grid: &mut Grid,
+        transforms: &mut ComponentList<Transform>,
+        sprites: &mut ComponentList<Sprite>,
+    ) -> bool {
+        if let Some(turn) = self.turns.pop() {
+            for grid_change in turn.changes.iter().rev() {
+                match grid_change {
+                    GridChange::Moved {
+                        entity,
+                        from,
+                        to,
+                        from_index,
+                        previous_local_position,
+                    } => {
+                        grid.remove(*to, *entity);
+                        if let Some(from_index) = from_index {
+                            grid.insert_at(*from, *from_index, *entity);
+                        }
+
+                        if let Some(transform) = transforms.get_mut(entity) {
+                            transform.inner_mut().set_local_position(*previous_local_position);
+                        } else {
+                            error!(
+                                "Entity {} moved on the Grid, but has no Transform to undo!",
+                                entity
+                            );
+                        }
+                    }
+
+                    GridChange::Destroyed {
+                        entity,
+                        position,
+                        index,
+                        sprite_was_active,
+                    } => {
+                        if let Some(index) = index {
+                            grid.insert_at(*position, *index, *entity);
+                        }
+
+                        if let Some(sprite) = sprites.get_mut(entity) {
+                            sprite.is_active = *sprite_was_active;
+                        }
+                    }
+                }
+            }
+
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        cardinals::CardinalPrime, grid_system, EntityAllocator, GridInfo, GridType, InteractionRules,
+        SceneSwitcher,
+    };
+    use super::*;
+    use std::collections::HashMap;
+
+    /// A Grid with just enough of an Ecs around it to take Turns and undo them.
+    struct Board {
+        grid: Grid,
+        grid_types: HashMap<Entity, GridType>,
+        transforms: ComponentList<Transform>,
+        sprites: ComponentList<Sprite>,
+        scene_switchers: ComponentList<SceneSwitcher>,
+        entity_allocator: EntityAllocator,
+        history: MoveHistory,
+    }
+
+    impl Board {
+        fn new() -> Self {
+            Self {
+                grid: Grid::new(GridInfo::default(), InteractionRules::default()),
+                grid_types: HashMap::new(),
+                transforms: ComponentList::new(),
+                sprites: ComponentList::new(),
+                scene_switchers: ComponentList::new(),
+                entity_allocator: EntityAllocator::new(),
+                history: MoveHistory::new(),
+            }
+        }
+
+        fn spawn(&mut self, grid_type: GridType, position: (usize, usize)) -> Entity {
+            let entity = self.entity_allocator.allocate();
+            self.transforms.expand_list();
+            self.sprites.expand_list();
+            self.scene_switchers.expand_list();
+
+            let world_position = self.grid.info().grid_to_world_position(position);
+            self.transforms
+                .set_component(&entity, Transform::new(world_position));
+            self.sprites.set_component(&entity, Sprite::default());
+            self.grid.insert(position, entity);
+            self.grid_types.insert(entity, grid_type);
+
+            entity
+        }
+
+        /// Moves the Entity one cell over, the way `update_grid_positions` does.
+        fn take_turn(&mut self, mover: Entity, movement: CardinalPrime) -> bool {
+            let from = self.grid.find(mover).unwrap();
+            let to = match self.grid.info().move_position(from, movement) {
+                Some(to) => to,
+                None => return false,
+            };
+
+            let grid_types = &self.grid_types;
+            let mut effects = vec![];
+            let moved = grid_system::attempt_to_move(
+                &mover,
+                grid_types[&mover],
+                from,
+                to,
+                movement,
+                &mut self.grid,
+                &|entity: &Entity| grid_types.get(entity).cloned(),
+                &mut effects,
+            );
+
+            let mut turn = Turn::default();
+            grid_system::apply_effects(
+                &self.grid,
+                effects,
+                &mut self.transforms,
+                &mut self.sprites,
+                &self.scene_switchers,
+                &mut turn,
+            );
+            self.history.push_turn(turn);
+
+            moved
+        }
+
+        fn undo(&mut self) -> bool {
+            self.history
+                .undo(&mut self.grid, &mut self.transforms, &mut self.sprites)
+        }
+
+        fn local_position(&self, entity: Entity) -> Vec2 {
+            self.transforms.get(&entity).unwrap().inner().local_position()
+        }
+
+        fn world_position_of(&self, position: (usize, usize)) -> Vec2 {
+            self.grid.info().grid_to_world_position(position)
+        }
+    }
+
+    #[test]
+    fn undoing_a_push_puts_both_entities_back() {
+        let mut board = Board::new();
+        let player = board.spawn(GridType::Player, (0, 0));
+        let pushable = board.spawn(GridType::Pushable, (1, 0));
+        let before = board.grid.snapshot();
+
+        assert!(board.take_turn(player, CardinalPrime::Right));
+        assert_eq!(board.grid.occupants((1, 0)), &[player]);
+        assert_eq!(board.grid.occupants((2, 0)), &[pushable]);
+        assert_eq!(board.local_position(pushable), board.world_position_of((2, 0)));
+        assert_eq!(board.history.turn_count(), 1);
+
+        assert!(board.undo());
+        assert_eq!(board.grid.snapshot(), before);
+        assert_eq!(board.local_position(player), board.world_position_of((0, 0)));
+        assert_eq!(board.local_position(pushable), board.world_position_of((1, 0)));
+
+        assert_eq!(board.history.turn_count(), 0);
+        assert!(board.undo() == false);
+    }
+
+    #[test]
+    fn undoing_several_turns_rewinds_them_in_order() {
+        let mut board = Board::new();
+        let player = board.spawn(GridType::Player, (0, 0));
+        let pushable = board.spawn(GridType::Pushable, (0, 2));
+        board.spawn(GridType::Blockable, (2, 2));
+
+        let mut snapshots = vec![board.grid.snapshot()];
+        for movement in [CardinalPrime::Up, CardinalPrime::Up, CardinalPrime::Right].iter() {
+            assert!(board.take_turn(player, *movement));
+            snapshots.push(board.grid.snapshot());
+        }
+
+        // Walking into the Blockable doesn't move anything, so there's no Turn to undo
+        assert!(board.take_turn(player, CardinalPrime::Right) == false);
+        assert_eq!(board.grid.occupants((0, 3)), &[pushable]);
+        assert_eq!(board.history.turn_count(), 3);
+
+        snapshots.pop();
+        while let Some(snapshot) = snapshots.pop() {
+            assert!(board.undo());
+            assert_eq!(board.grid.snapshot(), snapshot);
+        }
+
+        assert_eq!(board.local_position(player), board.world_position_of((0, 0)));
+        assert_eq!(board.local_position(pushable), board.world_position_of((0, 2)));
+        assert!(board.undo() == false);
+    }
+}
@@ -11,10 +11,12 @@ pub fn entity_inspector(
     let ui: &Ui<'_> = &ui_handler.ui;
     let mut remove_this_entity = None;
     let mut final_post_action: Option<ComponentInspectorPostAction> = None;
+    let mut commands = CommandBuffer::new();
+    let mut lacked_transforms = vec![];
 
     let Ecs {
         component_database,
-        singleton_database,
+        singleton_database: _,
         entity_allocator: _,
         entities,
     } = ecs;
@@ -48,6 +50,17 @@ pub fn entity_inspector(
             }
         };
 
+        // Which Prefab each of our components comes from, if we're a variant's inheritor
+        let override_origins = component_database
+            .prefab_markers
+            .get(entity)
+            .and_then(|pm| {
+                prefab_system::prefab_chain(resources.prefabs(), pm.inner().main_id(), pm.inner().sub_id())
+                    .ok()
+            })
+            .map(|prefab_chain| prefab_system::override_origins(&prefab_chain))
+            .unwrap_or_default();
+
         let names = &component_database.names;
         let serialized_entity = component_database
             .serialization_markers
@@ -90,13 +103,20 @@ pub fn entity_inspector(
             component_database.foreach_component_list_mut(
                 NonInspectableEntities::empty(),
                 |component_list| {
-                    let possible_sync_statuses = component_list.get_sync_status(
-                        entity,
-                        serialized_entity.as_ref(),
-                        serialized_prefab.as_ref(),
-                        should_have_serialized_entity,
-                        should_have_prefab,
-                    );
+                    let possible_sync_statuses = component_list
+                        .get_sync_status(
+                            entity,
+                            serialized_entity.as_ref(),
+                            serialized_prefab.as_ref(),
+                            should_have_serialized_entity,
+                            should_have_prefab,
+                        )
+                        .map(|mut sync_status| {
+                            sync_status.prefab_origin = override_origins
+                                .get(&component_list.get_yaml_component_key())
+                                .copied();
+                            sync_status
+                        });
 
                     if let Some(command_type) = component_list.component_inspector(
                         entity,
@@ -106,6 +126,7 @@ pub fn entity_inspector(
                         resources.prefabs(),
                         ui,
                         window_is_open,
+                        &mut commands,
                     ) {
                         final_post_action = Some(handle_serialization_command(
                             *entity,
@@ -134,6 +155,7 @@ pub fn entity_inspector(
                     s_marker,
                     SyncStatus::Synced,
                     SyncStatus::Unsynced,
+                    None,
                     entities,
                     &component_database.names,
                     resources.prefabs(),
@@ -179,23 +201,16 @@ pub fn entity_inspector(
                     true,
                 ) {
                     // @update_components exception
-                    let had_transform = component_database.transforms.get(entity).is_some();
+                    if component_database.transforms.get(entity).is_none() {
+                        lacked_transforms.push(*entity);
+                    }
 
                     // Prefab Marker, Name, Graph Node is omitted
                     component_database.foreach_component_list_mut(
                         NonInspectableEntities::SERIALIZATION,
-                        |component_list| component_list.component_add_button(entity, ui),
+                        |component_list| component_list.component_add_button(entity, ui, &mut commands),
                     );
 
-                    if had_transform == false {
-                        if let Some(new_transform) = component_database.transforms.get_mut(entity) {
-                            scene_graph::add_to_scene_graph(
-                                new_transform,
-                                &component_database.serialization_markers,
-                            );
-                        }
-                    }
-
                     add_component_submenu.end(ui);
                 }
 
@@ -235,6 +250,21 @@ pub fn entity_inspector(
         ui_handler.stored_ids.remove(&entity);
     }
 
+    // Components added or deleted above go in now, running their hooks
+    commands.apply(ecs, resources.prefabs());
+    for entity in lacked_transforms {
+        if let Some(new_transform) = ecs.component_database.transforms.get_mut(&entity) {
+            scene_graph::add_to_scene_graph(new_transform, &ecs.component_database.serialization_markers);
+        }
+    }
+
+    let Ecs {
+        component_database,
+        singleton_database,
+        entity_allocator: _,
+        entities: _,
+    } = ecs;
+
     let entity_command = if let Some(final_post_action) = final_post_action {
         match final_post_action {
             ComponentInspectorPostAction::ComponentCommands(command) => {
@@ -321,7 +351,7 @@ pub fn entity_inspector(
                         let mut prefab = serialization_util::prefabs::load_prefab(&main_id)?.unwrap();
                         let (new_member, _diff): (SerializedEntity, _) = {
                             let mut member_yaml =
-                                serde_yaml::to_value(prefab.members.get(&sub_id).cloned().unwrap())?;
+                                serde_yaml::to_value(prefab.member_overrides(&sub_id).unwrap())?;
 
                             let diff = member_yaml
                                 .as_mapping_mut()
@@ -341,6 +371,7 @@ pub fn entity_inspector(
                             command.key,
                             command.delta,
                             prefab_reload_required,
+                            resources.prefabs(),
                         )?;
                     }
                 }
@@ -416,6 +447,7 @@ pub fn component_inspector_raw<T>(
     comp: &mut Component<T>,
     serialization_sync_status: SyncStatus,
     prefab_sync_status: SyncStatus,
+    prefab_origin: Option<uuid::Uuid>,
     entities: &[Entity],
     entity_names: &ComponentList<Name>,
     prefabs: &PrefabMap,
@@ -453,12 +485,21 @@ where
             imgui_utility::wrap_style_var(ui, StyleVar::Alpha(1.0), || {
                 // This is the Hover here:
                 if ui.is_item_hovered() {
-                    ui.tooltip_text(match prefab_sync_status {
+                    let status = match prefab_sync_status {
                         SyncStatus::Unsynced => "This Entity does not inherit from a Prefab.",
                         SyncStatus::Headless => "This Componet is HEADLESS to its PREFAB!",
                         SyncStatus::OutofSync => "Overriding Prefab Parent",
                         SyncStatus::Synced => "Synced to Prefab Parent",
-                    });
+                    };
+
+                    match prefab_origin {
+                        Some(origin) => ui.tooltip_text(format!(
+                            "{}, which gets it from {}",
+                            status,
+                            prefab_system::prefab_name(prefabs, origin)
+                        )),
+                        None => ui.tooltip_text(status),
+                    }
                 }
 
                 // Sadly, we lack destructure assign
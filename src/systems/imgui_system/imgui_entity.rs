@@ -29,7 +29,7 @@ pub fn entity_list(
                 }
             }
             NameRequestedAction::Clone => {
-                let new_entity = ecs.clone_entity(&entity);
+                let new_entity = ecs.clone_entity(&entity, resources.prefabs());
 
                 let names: *const ComponentList<Name> = &mut ecs.component_database.names;
                 if let Some(name) = ecs.component_database.names.get_mut(&new_entity) {
@@ -37,7 +37,7 @@ pub fn entity_list(
                 }
             }
             NameRequestedAction::Delete => {
-                ecs.remove_entity(&entity);
+                ecs.remove_entity(&entity, resources.prefabs());
                 ui_handler.stored_ids.remove(&entity);
             }
             NameRequestedAction::GoToPrefab => {
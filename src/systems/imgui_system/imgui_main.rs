@@ -6,6 +6,7 @@ pub fn imgui_main(
     hardware_interfaces: &mut HardwareInterface,
     ui_handler: &mut UiHandler<'_>,
     time_keeper: &TimeKeeper,
+    scheduler: &mut Scheduler,
 ) {
     let mut entity_serialization_command: Option<EntitySerializationCommand> = None;
 
@@ -59,9 +60,23 @@ pub fn imgui_main(
         time_keeper.create_imgui_window(ui_handler)
     });
 
+    // Scheduler
+    imgui_utility::create_window(ui_handler, ImGuiFlags::SCHEDULER, |ui_handler| {
+        scheduler.create_imgui_window(ui_handler)
+    });
+
     // Resources Windows
     imgui_resources::create_resources_windows(resources, ui_handler);
 
+    // Input Bindings
+    imgui_utility::create_window(ui_handler, ImGuiFlags::INPUT_BINDINGS, |ui_handler| {
+        imgui_resources::input_bindings_editor(
+            &mut resources.input_bindings,
+            &mut hardware_interfaces.input,
+            ui_handler,
+        )
+    });
+
     // Demo window!
     if ui_handler.flags.contains(ImGuiFlags::IMGUI_EXAMPLE) {
         let mut is_closed = false;
@@ -214,6 +229,13 @@ fn main_menu_bar(toggle_main_menu_bar: bool, ui_handler: &mut UiHandler<'_>) {
                     &mut ui_handler.flags,
                 );
 
+                menu_option(
+                    im_str!("Systems"),
+                    ImGuiFlags::SCHEDULER,
+                    ui,
+                    &mut ui_handler.flags,
+                );
+
                 menu_option(
                     im_str!("Game Config Inspector"),
                     ImGuiFlags::GAME_CONFIG,
@@ -221,6 +243,13 @@ fn main_menu_bar(toggle_main_menu_bar: bool, ui_handler: &mut UiHandler<'_>) {
                     &mut ui_handler.flags,
                 );
 
+                menu_option(
+                    im_str!("Input Bindings"),
+                    ImGuiFlags::INPUT_BINDINGS,
+                    ui,
+                    &mut ui_handler.flags,
+                );
+
                 menu_option(
                     im_str!("Demo Window"),
                     ImGuiFlags::IMGUI_EXAMPLE,
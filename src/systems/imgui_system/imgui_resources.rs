@@ -260,6 +260,33 @@ pub fn game_config_editor(config: &mut game_config::Config, ui_handler: &mut UiH
         ui.input_float(&im_str!("ImGui Font Size##{}", uid), &mut config.imgui_pixel_size)
             .build();
 
+        // Volume
+        ui.separator();
+        for (label, volume) in [
+            ("Master Volume", &mut config.volume.master),
+            ("Music Volume", &mut config.volume.music),
+            ("SFX Volume", &mut config.volume.sfx),
+        ]
+        .iter_mut()
+        {
+            ui.drag_float(&im_str!("{}##{}", label, uid), volume)
+                .min(0.0)
+                .max(1.0)
+                .speed(0.01)
+                .build();
+        }
+
+        // Backups
+        ui.separator();
+        let mut backup_count = config.backup_count as i32;
+        if ui
+            .input_int(&im_str!("Backups Kept##{}", uid), &mut backup_count)
+            .build()
+        {
+            config.backup_count = backup_count.max(0) as usize;
+            safe_files::set_backup_count(config.backup_count);
+        }
+
         // Serialize
         if ui.button(&im_str!("Serialize##{}", uid), [-1.0, 0.0]) {
             if let Err(e) = serialization_util::game_config::serialize_config(config) {
@@ -275,6 +302,144 @@ pub fn game_config_editor(config: &mut game_config::Config, ui_handler: &mut UiH
     close
 }
 
+pub fn input_bindings_editor(
+    input_bindings: &mut InputBindings,
+    input: &mut Input,
+    ui_handler: &mut UiHandler<'_>,
+) -> bool {
+    let mut close = true;
+    let ui: &mut Ui<'_> = &mut ui_handler.ui;
+    let input_bindings_window = imgui::Window::new(imgui::im_str!("Input Bindings"))
+        .size(Vec2::new(350.0, 400.0).into(), imgui::Condition::FirstUseEver)
+        .opened(&mut close);
+
+    if let Some(window) = input_bindings_window.begin(ui) {
+        let uid = "input_bindings";
+
+        let gamepad_status = if input.gamepad_input.connected {
+            "Connected"
+        } else {
+            "Not Connected"
+        };
+        ui.label_text(&im_str!("Gamepad##{}", uid), &im_str!("{}", gamepad_status));
+        ui.separator();
+
+        // Waiting on a Key. We eat the Key, so it doesn't also move a Player.
+        if let Some(action) = input_bindings.rebinding {
+            ui.text_colored(
+                imgui_utility::yellow_warning_color(),
+                &im_str!(
+                    "Press a key, gamepad button, right or middle click for {}. Escape cancels.",
+                    action
+                ),
+            );
+
+            let new_binding = if let Some(key) = input.kb_input.pressed_keys.first().cloned() {
+                input.kb_input.eat_key(key);
+                if key == winit::event::VirtualKeyCode::Escape {
+                    input_bindings.rebinding = None;
+                    None
+                } else {
+                    Some(Binding::Key(key))
+                }
+            } else if let Some(button) = input.gamepad_input.pressed_buttons.first() {
+                Some(Binding::Gamepad(*button))
+            } else if let Some(direction) = input.gamepad_input.stick_pressed() {
+                Some(Binding::GamepadStick(direction))
+            } else if input.mouse_input.mouse_pressed[1] {
+                Some(Binding::Mouse(MouseButton::Right))
+            } else if input.mouse_input.mouse_pressed[2] {
+                Some(Binding::Mouse(MouseButton::Middle))
+            } else {
+                None
+            };
+
+            if let Some(new_binding) = new_binding {
+                input_bindings.bind(action, new_binding);
+                input_bindings.rebinding = None;
+            }
+            ui.separator();
+        }
+
+        for action in <InputAction as strum::IntoEnumIterator>::iter() {
+            ui.text(&im_str!("{}", action));
+
+            let mut remove = None;
+            for (i, binding) in input_bindings.bindings_for(action).iter().enumerate() {
+                let binding_name = match binding {
+                    Binding::Key(key) => format!("{:?}", key),
+                    Binding::Mouse(mouse_button) => format!("{:?} Mouse", mouse_button),
+                    Binding::Gamepad(button) => format!("Pad {}", button),
+                    Binding::GamepadStick(direction) => format!("Pad Stick {}", direction),
+                };
+
+                ui.same_line(0.0);
+                if ui.small_button(&im_str!("{}##{}{}{}", binding_name, uid, action, i)) {
+                    remove = Some(i);
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Click to remove");
+                }
+            }
+
+            if let Some(remove) = remove {
+                input_bindings.bindings.entry(action).or_default().remove(remove);
+            }
+
+            ui.same_line(0.0);
+            if ui.small_button(&im_str!("+##{}{}", uid, action)) {
+                input_bindings.rebinding = Some(action);
+            }
+        }
+
+        // Hold to Repeat
+        ui.separator();
+        ui.checkbox(
+            &im_str!("Hold to Repeat##{}", uid),
+            &mut input_bindings.hold_to_repeat,
+        );
+        if input_bindings.hold_to_repeat {
+            if ui
+                .input_float(
+                    &im_str!("Repeat Delay##{}", uid),
+                    &mut input_bindings.repeat_delay,
+                )
+                .build()
+            {
+                input_bindings.repeat_delay = input_bindings.repeat_delay.max(0.0);
+            }
+
+            if ui
+                .input_float(
+                    &im_str!("Repeat Interval##{}", uid),
+                    &mut input_bindings.repeat_interval,
+                )
+                .build()
+            {
+                input_bindings.repeat_interval = input_bindings.repeat_interval.max(0.01);
+            }
+        }
+
+        ui.spacing();
+        if ui.button(&im_str!("Reset to Defaults##{}", uid), [-1.0, 0.0]) {
+            *input_bindings = InputBindings::default();
+        }
+
+        // Serialize
+        if ui.button(&im_str!("Serialize##{}", uid), [-1.0, 0.0]) {
+            if let Err(e) = serialization_util::input_bindings::serialize_input_bindings(input_bindings) {
+                error!(
+                    "Couldn't serialize the Input Bindings! Warning! Your data may be lost! {}",
+                    e
+                )
+            }
+        }
+        window.end(ui)
+    }
+
+    close
+}
+
 pub fn prefab_entity_viewer(resources: &mut ResourcesDatabase, ui_handler: &mut UiHandler<'_>) -> bool {
     let mut open = true;
 
@@ -27,6 +27,42 @@ pub fn singleton_inspector(
             |serialized, live| *live = serialized.camera,
         );
 
+        inspect_this_singleton_component(
+            &mut sd.grid,
+            &mut sd.associated_entities,
+            cd_n,
+            entities,
+            prefab_hashmap,
+            ui_handler,
+            is_open,
+            |serialized, live| serialized.grid = live.clone(),
+            |serialized, live| *live = serialized.grid,
+        );
+
+        inspect_this_singleton_component(
+            &mut sd.level,
+            &mut sd.associated_entities,
+            cd_n,
+            entities,
+            prefab_hashmap,
+            ui_handler,
+            is_open,
+            |serialized, live| serialized.level = live.clone(),
+            |serialized, live| *live = serialized.level,
+        );
+
+        inspect_this_singleton_component(
+            &mut sd.music,
+            &mut sd.associated_entities,
+            cd_n,
+            entities,
+            prefab_hashmap,
+            ui_handler,
+            is_open,
+            |serialized, live| serialized.music = live.clone(),
+            |serialized, live| *live = serialized.music,
+        );
+
         // inspect_this_singleton_component(
         //     &mut sd.player,
         //     &mut sd.associated_entities,
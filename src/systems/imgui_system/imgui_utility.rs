@@ -172,6 +172,36 @@ where
     variant
 }
 
+/// A text box for naming a sound in `assets/audio`. Clearing it gives None.
+pub fn sound_name_input(
+    ui: &Ui<'_>,
+    current_value: &Option<SoundName>,
+    label: &str,
+    uid: &str,
+) -> Option<Option<SoundName>> {
+    let mut sound_name = ImString::new(
+        current_value
+            .as_ref()
+            .map_or("", |sound_name| sound_name.as_str()),
+    );
+
+    if ui
+        .input_text(&im_str!("{}##{}", label, uid), &mut sound_name)
+        .resize_buffer(true)
+        .enter_returns_true(true)
+        .build()
+    {
+        let sound_name = sound_name.to_str().trim();
+        Some(if sound_name.is_empty() {
+            None
+        } else {
+            Some(SoundName::new(sound_name))
+        })
+    } else {
+        None
+    }
+}
+
 pub fn select_entity_option(
     label: &str,
     current_value: &Option<Entity>,
@@ -1,4 +1,4 @@
-use super::{ImGui, Input, Vec2};
+use super::{GamepadSource, ImGui, Input, Vec2, MOUSE_BUTTON_COUNT};
 use imgui_winit_support::WinitPlatform;
 use winit::{
     dpi::LogicalPosition,
@@ -13,6 +13,7 @@ use winit::{
 
 pub fn poll_events(
     input_component: &mut Input,
+    gamepad_source: &mut dyn GamepadSource,
     events_loop: &mut EventLoop<()>,
     winit_window: &WinitWindow,
     imgui: &mut ImGui,
@@ -84,7 +85,7 @@ pub fn poll_events(
                     WinitMouseButton::Other(num) => num as usize,
                 };
 
-                if mouse_button_clicked_last_frame[this_button] == false {
+                if this_button < MOUSE_BUTTON_COUNT && mouse_button_clicked_last_frame[this_button] == false {
                     input_component.mouse_input.mouse_pressed[this_button] = true;
                     input_component.mouse_input.mouse_held[this_button] = true;
                 }
@@ -106,8 +107,9 @@ pub fn poll_events(
                     WinitMouseButton::Other(num) => num as usize,
                 };
 
-                if input_component.mouse_input.mouse_pressed[this_button]
-                    || input_component.mouse_input.mouse_held[this_button]
+                if this_button < MOUSE_BUTTON_COUNT
+                    && (input_component.mouse_input.mouse_pressed[this_button]
+                        || input_component.mouse_input.mouse_held[this_button])
                 {
                     input_component.mouse_input.mouse_pressed[this_button] = false;
                     input_component.mouse_input.mouse_held[this_button] = false;
@@ -151,6 +153,18 @@ pub fn poll_events(
             _ => {}
         }
     });
+
+    poll_gamepad_events(input_component, gamepad_source);
+}
+
+/// Records every event waiting in the GamepadSource. This doesn't need a
+/// window, so a MockGamepadSource can drive it.
+pub fn poll_gamepad_events(input_component: &mut Input, gamepad_source: &mut dyn GamepadSource) {
+    while let Some(event) = gamepad_source.next_event() {
+        input_component.gamepad_input.record_event(event);
+    }
+
+    input_component.gamepad_input.update_stick();
 }
 
 pub fn imgui_event_handler<T>(
@@ -0,0 +1,296 @@
+use super::{
+    grid_system::Grid, scene_system, ComponentList, Entity, GoalCondition, GridObject, GridType, LevelGoals,
+    Scene,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LevelOutcome {
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LevelEvent {
+    Completed { turns_taken: usize },
+    Failed { turns_taken: usize },
+}
+
+/// The progress of the current Scene. Events only live until the start of
+/// the next Turn, so read them right after `update_level`.
+#[derive(Debug, Default)]
+pub struct LevelState {
+    turns_taken: usize,
+    outcome: Option<LevelOutcome>,
+    events: Vec<LevelEvent>,
+}
+
+impl LevelState {
+    pub fn turns_taken(&self) -> usize {
+        self.turns_taken
+    }
+
+    pub fn outcome(&self) -> Option<LevelOutcome> {
+        self.outcome
+    }
+
+    pub fn events(&self) -> &[LevelEvent] {
+        &self.events
+    }
+
+    /// Gives back a Turn after an undo. The board freezes once there's an outcome,
+    /// so the Turn being undone is the one which caused it, and the outcome goes too.
+    pub fn undo_turn(&mut self) {
+        self.turns_taken = self.turns_taken.saturating_sub(1);
+        self.outcome = None;
+    }
+}
+
+pub fn update_level(
+    level_state: &mut LevelState,
+    turn_taken: bool,
+    goals: &LevelGoals,
+    grid_objects: &ComponentList<GridObject>,
+    grid: &Grid,
+) {
+    level_state.events.clear();
+
+    if turn_taken == false || level_state.outcome.is_some() {
+        return;
+    }
+    level_state.turns_taken += 1;
+
+    if goals_met(goals, grid, &|entity: &Entity| {
+        grid_objects.get(entity).map(|g| g.inner().grid_type())
+    }) {
+        info!("Level completed in {} turns!", level_state.turns_taken);
+        level_state.outcome = Some(LevelOutcome::Completed);
+        level_state.events.push(LevelEvent::Completed {
+            turns_taken: level_state.turns_taken,
+        });
+
+        if let Some(next_scene) = &goals.next_scene {
+            if scene_system::set_next_scene(Scene::new(next_scene.clone())) == false {
+                error!("Couldn't switch scenes! Does {} exist?", next_scene);
+            }
+        }
+    } else if goals
+        .turn_limit
+        .map_or(false, |turn_limit| level_state.turns_taken >= turn_limit)
+    {
+        info!("Level failed after {} turns!", level_state.turns_taken);
+        level_state.outcome = Some(LevelOutcome::Failed);
+        level_state.events.push(LevelEvent::Failed {
+            turns_taken: level_state.turns_taken,
+        });
+
+        if goals.restart_on_failure {
+            let current_scene = Scene::new(scene_system::current_scene_name());
+            if scene_system::set_next_scene(current_scene) == false {
+                error!("Couldn't restart the Scene!");
+            }
+        }
+    }
+}
+
+/// Returns true if every condition is met. A Scene with no conditions is
+/// never complete -- it'll have to be left through a SceneSwitcher.
+pub fn goals_met(
+    goals: &LevelGoals,
+    grid: &Grid,
+    grid_type_of: &impl Fn(&Entity) -> Option<GridType>,
+) -> bool {
+    if goals.conditions.is_empty() {
+        return false;
+    }
+
+    goals.conditions.iter().all(|condition| match condition {
+        GoalCondition::AllOn { mover, target } => all_on(*mover, *target, grid, grid_type_of),
+        GoalCondition::EventRaised(name) => grid.events().iter().any(|event| &event.name == name),
+    })
+}
+
+/// Only movers which are on the Grid count, so a destroyed mover doesn't
+/// hold the level up.
+fn all_on(
+    mover: GridType,
+    target: GridType,
+    grid: &Grid,
+    grid_type_of: &impl Fn(&Entity) -> Option<GridType>,
+) -> bool {
+    let mut total_movers = 0;
+    let mut satisfied_movers = 0;
+    for pos in grid.positions() {
+        let grid_types: Vec<GridType> = grid.occupants(pos).iter().filter_map(grid_type_of).collect();
+        let movers_here = grid_types.iter().filter(|grid_type| **grid_type == mover).count();
+
+        total_movers += movers_here;
+        if grid_types.contains(&target) {
+            satisfied_movers += movers_here;
+        }
+    }
+
+    total_movers != 0 && satisfied_movers == total_movers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cardinals::CardinalPrime, grid_system, EntityAllocator, GridInfo, Interaction, InteractionRule,
+        InteractionRules,
+    };
+
+    struct Board {
+        grid: Grid,
+        entity_allocator: EntityAllocator,
+        grid_objects: ComponentList<GridObject>,
+    }
+
+    impl Board {
+        fn new(rules: InteractionRules) -> Self {
+            Self {
+                grid: Grid::new(GridInfo::default(), rules),
+                entity_allocator: EntityAllocator::new(),
+                grid_objects: ComponentList::new(),
+            }
+        }
+
+        fn spawn(&mut self, grid_type: GridType, position: (usize, usize)) -> Entity {
+            let entity = self.entity_allocator.allocate();
+            self.grid_objects.expand_list();
+            self.grid_objects.set_component(
+                &entity,
+                GridObject {
+                    grid_type,
+                    ..Default::default()
+                },
+            );
+            self.grid.insert(position, entity);
+
+            entity
+        }
+
+        fn goals_met(&self, goals: &LevelGoals) -> bool {
+            let grid_objects = &self.grid_objects;
+            goals_met(goals, &self.grid, &|entity: &Entity| {
+                grid_objects.get(entity).map(|g| g.inner().grid_type())
+            })
+        }
+    }
+
+    fn all_pushables_on_flags() -> LevelGoals {
+        LevelGoals {
+            conditions: vec![GoalCondition::AllOn {
+                mover: GridType::Pushable,
+                target: GridType::Flag,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn goals_are_met_once_every_condition_is() {
+        let mut board = Board::new(InteractionRules {
+            rules: vec![InteractionRule::new(
+                Some(GridType::Player),
+                GridType::Custom(0),
+                Interaction::TriggerEvent("opened".to_string()),
+            )],
+        });
+        assert!(board.goals_met(&LevelGoals::default()) == false);
+
+        board.spawn(GridType::Flag, (1, 1));
+        board.spawn(GridType::Pushable, (1, 1));
+        let stray_pushable = board.spawn(GridType::Pushable, (2, 2));
+        board.spawn(GridType::Flag, (3, 3));
+        let mut goals = all_pushables_on_flags();
+        assert!(board.goals_met(&goals) == false);
+
+        board.grid.move_entity(stray_pushable, (2, 2), (3, 3));
+        assert!(board.goals_met(&goals));
+
+        goals
+            .conditions
+            .push(GoalCondition::EventRaised("opened".to_string()));
+        assert!(board.goals_met(&goals) == false);
+
+        let player = board.spawn(GridType::Player, (0, 4));
+        board.spawn(GridType::Custom(0), (1, 4));
+        let grid_objects = &board.grid_objects;
+        let moved = grid_system::attempt_to_move(
+            &player,
+            GridType::Player,
+            (0, 4),
+            (1, 4),
+            CardinalPrime::Right,
+            &mut board.grid,
+            &|entity: &Entity| grid_objects.get(entity).map(|g| g.inner().grid_type()),
+            &mut vec![],
+        );
+        assert!(moved);
+        assert!(board.goals_met(&goals));
+    }
+
+    #[test]
+    fn all_on_only_counts_movers_on_the_grid() {
+        let mut board = Board::new(InteractionRules::default());
+        let goals = all_pushables_on_flags();
+
+        // With no movers at all, there's nothing to be on anything
+        board.spawn(GridType::Flag, (1, 1));
+        assert!(board.goals_met(&goals) == false);
+
+        board.spawn(GridType::Pushable, (1, 1));
+        let destroyed_pushable = board.spawn(GridType::Pushable, (2, 2));
+        assert!(board.goals_met(&goals) == false);
+
+        board.grid.remove((2, 2), destroyed_pushable);
+        assert!(board.goals_met(&goals));
+    }
+
+    #[test]
+    fn the_level_fails_once_the_turn_limit_is_reached() {
+        let mut board = Board::new(InteractionRules::default());
+        board.spawn(GridType::Flag, (1, 1));
+        board.spawn(GridType::Pushable, (2, 2));
+        let goals = LevelGoals {
+            turn_limit: Some(2),
+            ..all_pushables_on_flags()
+        };
+
+        let mut level_state = LevelState::default();
+        update_level(&mut level_state, false, &goals, &board.grid_objects, &board.grid);
+        update_level(&mut level_state, true, &goals, &board.grid_objects, &board.grid);
+        assert_eq!(level_state.turns_taken(), 1);
+        assert_eq!(level_state.outcome(), None);
+
+        update_level(&mut level_state, true, &goals, &board.grid_objects, &board.grid);
+        assert_eq!(level_state.outcome(), Some(LevelOutcome::Failed));
+        assert_eq!(level_state.events(), &[LevelEvent::Failed { turns_taken: 2 }]);
+
+        // The board is frozen once there's an outcome, so no more Turns are counted
+        update_level(&mut level_state, true, &goals, &board.grid_objects, &board.grid);
+        assert_eq!(level_state.turns_taken(), 2);
+        assert!(level_state.events().is_empty());
+    }
+
+    #[test]
+    fn undoing_the_last_turn_takes_its_outcome_back() {
+        let mut board = Board::new(InteractionRules::default());
+        board.spawn(GridType::Flag, (1, 1));
+        board.spawn(GridType::Pushable, (1, 1));
+        let goals = all_pushables_on_flags();
+
+        let mut level_state = LevelState::default();
+        update_level(&mut level_state, true, &goals, &board.grid_objects, &board.grid);
+        assert_eq!(level_state.outcome(), Some(LevelOutcome::Completed));
+        assert_eq!(level_state.events(), &[LevelEvent::Completed { turns_taken: 1 }]);
+
+        level_state.undo_turn();
+        assert_eq!(level_state.turns_taken(), 0);
+        assert_eq!(level_state.outcome(), None);
+
+        update_level(&mut level_state, true, &goals, &board.grid_objects, &board.grid);
+        assert_eq!(level_state.outcome(), Some(LevelOutcome::Completed));
+    }
+}
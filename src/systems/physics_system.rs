@@ -1,6 +1,5 @@
 use super::{
-    physics_components::BoundingBox, Axis, ComponentList, Entity, PositionalRect, Rect,
-    Transform, Vec2,
+    physics_components::BoundingBox, Axis, ComponentList, Entity, Join, PositionalRect, Rect, Transform, Vec2,
 };
 
 // @techdebt. We need a better solution than this!
@@ -13,17 +12,10 @@ pub fn create_positional_rect_lists(
     let mut out_list = Vec::new();
     let mut our_bb = None;
 
-    for this_comp in object_bbs.iter() {
-        let pos = transforms
-            .get(&this_comp.entity_id())
-            .unwrap()
-            .inner()
-            .world_position();
-        let rect = this_comp.inner().rect;
+    for (this_entity, (this_bb, transform)) in (object_bbs, transforms).join() {
+        let this_positional_rec = PositionalRect::new(transform.world_position(), this_bb.rect);
 
-        let this_positional_rec = PositionalRect::new(pos, rect);
-
-        if this_comp.entity_id() == entity_id {
+        if this_entity == entity_id {
             our_bb = Some(this_positional_rec);
         } else {
             out_list.push(this_positional_rec);
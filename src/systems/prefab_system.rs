@@ -95,7 +95,7 @@ pub fn instantiate_entity_from_prefab(
             .prefab_markers
             .set_component(&entity, PrefabMarker::new_main(prefab_id));
     } else {
-        if ecs.remove_entity(&entity) == false {
+        if ecs.remove_entity(&entity, prefab_map) == false {
             error!("We couldn't remove the Entity either, so we have a dangler!");
         }
     }
@@ -128,12 +128,14 @@ pub fn serialize_and_cache_prefab(
 }
 
 /// Use this to finish a prefab serialization. This is a fairly huge operation,
-/// so be careful with it.
+/// so be careful with it. Inheritors of variants of the Prefab get the delta too,
+/// unless a variant in between overrides it.
 pub fn post_prefab_serialization(
     ecs: &mut Ecs,
     key: serde_yaml::Value,
     delta: serde_yaml::Value,
     prefab_load: PrefabLoadRequired,
+    prefabs: &PrefabMap,
 ) -> Result<()> {
     let PrefabLoadRequired { main_id, sub_id } = prefab_load;
     let mut post_deserialization = None;
@@ -146,7 +148,7 @@ pub fn post_prefab_serialization(
             .get(entity)
             .map(|pmc| {
                 let pm = pmc.inner();
-                pm.main_id() == main_id && pm.sub_id() == sub_id
+                inherits_component(prefabs, pm.main_id(), pm.sub_id(), main_id, sub_id, &key)
             })
             .unwrap_or_default()
         {
@@ -217,7 +219,8 @@ pub fn post_prefab_serialization(
 /// This gets the parent prefab of a given inheritor.
 /// To make this simpler, imagine Player's parent Prefab is
 /// Actor. If Player's entity was passed into this method,
-/// a Serialized Actor would come out.
+/// a Serialized Actor would come out. If Actor is a variant,
+/// that's all of Actor, with its bases' components under it.
 ///
 /// Returns a **flag** indicating if a prefab was found,
 /// which will have been loaded into the SerializedEntity provided.
@@ -227,19 +230,22 @@ pub fn get_serialized_parent_prefab_from_inheritor(
     serialized_entity: &mut SerializedEntity,
 ) -> bool {
     if let Some(prefab_component) = maybe_prefab_marker {
-        let prefab = match resources.prefabs().get(&prefab_component.inner().main_id()) {
-            Some(i) => i,
-            None => return false,
-        };
-
-        let mut serialized_prefab = match prefab.members.get(&prefab_component.inner().sub_id()) {
-            Some(sp) => sp.clone(),
-            None => return false,
+        let prefab_marker = prefab_component.inner();
+        let mut serialized_prefab = match resolve_prefab_member(
+            resources.prefabs(),
+            prefab_marker.main_id(),
+            prefab_marker.sub_id(),
+        ) {
+            Ok(sp) => sp,
+            Err(e) => {
+                error!("{}", e);
+                return false;
+            }
         };
 
         serialized_prefab.prefab_marker = Some(SerializedComponent {
             active: true,
-            inner: prefab_component.inner().clone(),
+            inner: prefab_marker.clone(),
         });
 
         *serialized_entity = serialized_prefab;
@@ -249,6 +255,144 @@ pub fn get_serialized_parent_prefab_from_inheritor(
     }
 }
 
+/// One level of a Prefab's inheritance: the overrides which `prefab_id` saves
+/// for one of its members.
+#[derive(Debug, Clone)]
+pub struct PrefabLevel {
+    pub prefab_id: Uuid,
+    pub member_id: Uuid,
+    pub member: SerializedEntity,
+}
+
+/// Every level which a member of a Prefab is made out of. The first level is the
+/// member itself, and each level after is the one it's based on, down to a Prefab
+/// which isn't a variant.
+pub fn prefab_chain(prefabs: &PrefabMap, prefab_id: Uuid, member_id: Uuid) -> Result<Vec<PrefabLevel>> {
+    let mut chain: Vec<PrefabLevel> = vec![];
+    let mut next = Some((prefab_id, member_id));
+
+    while let Some((prefab_id, member_id)) = next {
+        if chain
+            .iter()
+            .any(|level| level.prefab_id == prefab_id && level.member_id == member_id)
+        {
+            bail!(
+                "Prefab {} inherits from itself, through member {}!",
+                prefab_id,
+                member_id
+            );
+        }
+
+        let member = prefabs
+            .get(&prefab_id)
+            .ok_or_else(|| anyhow!("We couldn't find Prefab {}", prefab_id))?
+            .member_overrides(&member_id)
+            .ok_or_else(|| anyhow!("Prefab {} has no member {}", prefab_id, member_id))?;
+
+        next = member
+            .prefab_marker
+            .as_ref()
+            .map(|pm| (pm.inner.main_id(), pm.inner.sub_id()));
+
+        chain.push(PrefabLevel {
+            prefab_id,
+            member_id,
+            member,
+        });
+    }
+
+    Ok(chain)
+}
+
+/// A member of a Prefab, with every level of its `prefab_chain` applied, starting
+/// from the bottom. The result has no PrefabMarker of its own.
+pub fn resolve_prefab_member(
+    prefabs: &PrefabMap,
+    prefab_id: Uuid,
+    member_id: Uuid,
+) -> Result<SerializedEntity> {
+    let mut chain = prefab_chain(prefabs, prefab_id, member_id)?.into_iter().rev();
+
+    let mut resolved = chain.next().unwrap().member;
+    for level in chain {
+        resolved = load_override_into_prefab(resolved, level.member)?;
+    }
+
+    resolved.id = member_id;
+    resolved.prefab_marker = None;
+    Ok(resolved)
+}
+
+/// The Prefab which gives each component (by its serialization key) to the first level
+/// of the chain. Components which none of the levels have aren't in the map.
+pub fn override_origins(chain: &[PrefabLevel]) -> HashMap<YamlValue, Uuid> {
+    let mut origins = HashMap::new();
+
+    // We start from the bottom, so that the levels above it win
+    for level in chain.iter().rev() {
+        if let Ok(YamlValue::Mapping(member)) = serde_yaml::to_value(&level.member) {
+            for (key, component) in member {
+                if component.is_null() == false {
+                    origins.insert(key, level.prefab_id);
+                }
+            }
+        }
+    }
+
+    origins
+}
+
+/// The name of a Prefab's RootEntity, which might come from the Prefab it's based on.
+pub fn prefab_name(prefabs: &PrefabMap, prefab_id: Uuid) -> String {
+    let root_entity = resolve_prefab_member(prefabs, prefab_id, prefab_id).ok();
+    let name = root_entity
+        .as_ref()
+        .and_then(|root_entity| root_entity.name.as_ref())
+        .map(|name| name.inner.name.as_str());
+
+    Name::get_name_even_quicklier(name, prefab_id)
+}
+
+// Whether an inheritor of `inheritor_main_id`'s member gets the component from
+// `main_id`'s member, rather than from a variant in between.
+fn inherits_component(
+    prefabs: &PrefabMap,
+    inheritor_main_id: Uuid,
+    inheritor_sub_id: Uuid,
+    main_id: Uuid,
+    sub_id: Uuid,
+    key: &YamlValue,
+) -> bool {
+    let chain = match prefab_chain(prefabs, inheritor_main_id, inheritor_sub_id) {
+        Ok(chain) => chain,
+        Err(_) => return false,
+    };
+
+    for level in chain {
+        if level.prefab_id == main_id && level.member_id == sub_id {
+            return true;
+        }
+
+        if overrides_component(&level.member, key) {
+            return false;
+        }
+    }
+
+    false
+}
+
+fn overrides_component(member: &SerializedEntity, key: &YamlValue) -> bool {
+    serde_yaml::to_value(member)
+        .ok()
+        .and_then(|member| {
+            member
+                .as_mapping()?
+                .get(key)
+                .map(|component| component.is_null() == false)
+        })
+        .unwrap_or_default()
+}
+
 /// This uses the *experimental* idea of some dynamic typings in YAML! These unwraps *should*
 /// be safe, as we know that SerializedEntity can be safely serialized and deserialized.
 pub fn load_override_into_prefab(
@@ -262,8 +406,15 @@ pub fn load_override_into_prefab(
 
     if let YamlValue::Mapping(mapping) = se_override_yaml {
         for (key, value) in mapping {
-            if value != serde_yaml::Value::Null {
-                prefab_serialized_value_as_map.insert(key, value);
+            if value.is_null() {
+                continue;
+            }
+
+            match prefab_serialized_value_as_map.get_mut(&key) {
+                Some(base) => merge_yaml(base, value),
+                None => {
+                    prefab_serialized_value_as_map.insert(key, value);
+                }
             }
         }
     }
@@ -271,3 +422,25 @@ pub fn load_override_into_prefab(
     serde_yaml::from_value(prefab_serialized_yaml)
         .with_context(|| format!("We could not transform a composed YAML SE back to SE",))
 }
+
+// Lays `overrides` over `base`, going down into mappings (such as a component's `inner`)
+// so that whatever the override doesn't say is left as the base had it. Two mappings
+// which share no keys aren't the same shape (say, two variants of an enum), so there
+// the override replaces the base outright.
+fn merge_yaml(base: &mut YamlValue, overrides: YamlValue) {
+    match (base, overrides) {
+        (YamlValue::Mapping(base), YamlValue::Mapping(overrides))
+            if overrides.iter().any(|(key, _)| base.contains_key(key)) =>
+        {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_yaml(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
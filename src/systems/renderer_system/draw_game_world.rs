@@ -6,7 +6,7 @@ use gfx_hal::{
 };
 
 use super::{
-    sprite_system, BasicTextures, DrawingError, GameWorldDrawCommands, LoadedImage, PipelineBundle,
+    sprite_system, BasicTextures, DrawingError, GameWorldDrawCommands, Join, LoadedImage, PipelineBundle,
     RenderingUtility, StandardPushConstants, StandardQuadFactory, StandardTexture, TextureDescription,
     VertexIndexPairBufferBundle,
 };
@@ -68,36 +68,22 @@ pub(super) unsafe fn draw_game_world<'a>(
     } = rendering_utility;
 
     quad_buffer.clear();
-    for this_sprite in sprites.iter() {
-        if let Some(transform) = transforms.get(&this_sprite.entity_id()) {
-            quad_buffer.push(sprite_system::to_standard_quad(
-                this_sprite.inner(),
-                transform.inner().world_position(),
-                resources,
-            ));
-        }
+    for (_, (this_sprite, transform)) in (sprites, transforms).join() {
+        quad_buffer.push(sprite_system::to_standard_quad(
+            this_sprite,
+            transform.world_position(),
+            resources,
+        ));
     }
 
-    for text_source in text_sources.iter() {
-        if let Some(transform) = transforms.get(&text_source.entity_id()) {
-            for quad in &text_source.inner().cached_quads {
-                quad_buffer.push(
-                    text_source
-                        .inner()
-                        .prepare_standard_quad(transform.inner().world_position(), quad),
-                );
-            }
+    for (_, (text_source, transform)) in (text_sources, transforms).join() {
+        for quad in &text_source.cached_quads {
+            quad_buffer.push(text_source.prepare_standard_quad(transform.world_position(), quad));
         }
     }
 
-    for this_draw_rect in rects.iter() {
-        if let Some(transform) = transforms.get(&this_draw_rect.entity_id()) {
-            quad_buffer.push(
-                this_draw_rect
-                    .inner()
-                    .to_standard_quad(transform.inner().world_position()),
-            );
-        }
+    for (_, (this_draw_rect, transform)) in (rects, transforms).join() {
+        quad_buffer.push(this_draw_rect.to_standard_quad(transform.world_position()));
     }
     // for this_tilemap in tilemaps.iter() {
     //     if let Some(transform) = transforms.get(&this_tilemap.entity_id()) {
@@ -117,14 +103,7 @@ pub(super) unsafe fn draw_game_world<'a>(
         &[],
     );
 
-    let camera_position = camera_entity
-        .map(|camera_entity| {
-            transforms
-                .get(camera_entity)
-                .map(|tc| tc.inner().world_position())
-                .unwrap_or_else(|| camera.default_position)
-        })
-        .unwrap_or_else(|| camera.default_position);
+    let camera_position = camera.world_position(camera_entity, transforms);
 
     for quad in quad_buffer {
         let mut push_constants = StandardPushConstants::with_camera_data(camera_position, camera);
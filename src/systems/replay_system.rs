@@ -0,0 +1,282 @@
+use super::{
+    grid_system::{Grid, GridCell},
+    history_system::MoveHistory,
+    level_system::LevelState,
+    scene_graph, scene_system, serialization_util, ActionMap, CommandBuffer, Ecs, SceneMode, Scheduler,
+    SystemParameters, Tick, TimeKeeper, SYSTEMS,
+};
+use anyhow::Error;
+
+/// The ActionMap for one Fixed tick of the Scheduler. A frame can run several
+/// Fixed ticks, so ticks can repeat.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub tick: u64,
+    pub actions: ActionMap,
+}
+
+/// A recording of everything the player asked for, starting from a fresh
+/// load of `scene`. Empty ActionMaps aren't recorded, since they never change
+/// the board. Editing the board through ImGui while recording won't be replayed!
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Replay {
+    pub scene: String,
+    pub frames: Vec<ReplayFrame>,
+    /// The Scene and Grid when recording stopped, so a playback can be checked.
+    pub final_scene: Option<String>,
+    pub final_grid: Option<Vec<GridCell>>,
+}
+
+pub struct ReplayRecorder {
+    path: String,
+    replay: Replay,
+}
+
+impl ReplayRecorder {
+    /// Starts recording from `scene`. For the Replay to play back correctly,
+    /// the Scene must not have been touched yet.
+    pub fn new(path: String, scene: String) -> Self {
+        info!("Recording a Replay of {} to {}", scene, path);
+
+        Self {
+            path,
+            replay: Replay {
+                scene,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn record(&mut self, tick: u64, actions: &ActionMap) {
+        if actions.is_empty() == false {
+            self.replay.frames.push(ReplayFrame {
+                tick,
+                actions: actions.clone(),
+            });
+        }
+    }
+
+    /// Saves the Replay, along with the Scene and board as they are now.
+    pub fn finish(self, final_scene: String, grid: &Grid) -> Result<(), Error> {
+        let path = self.path.clone();
+        let replay = self.into_replay(final_scene, grid);
+
+        serialization_util::replays::serialize_replay(&replay, &path)?;
+        info!("Saved a Replay of {} frames to {}", replay.frames.len(), path);
+
+        Ok(())
+    }
+
+    fn into_replay(mut self, final_scene: String, grid: &Grid) -> Replay {
+        self.replay.final_scene = Some(final_scene);
+        self.replay.final_grid = Some(grid.snapshot());
+
+        self.replay
+    }
+}
+
+/// A Scene being played through the headless Fixed systems, without a window.
+pub struct Playback {
+    /// The Scene we're in now, which changes as we follow SceneSwitchers.
+    pub scene: String,
+    pub ecs: Ecs,
+    pub grid: Grid,
+    pub level_state: LevelState,
+    move_history: MoveHistory,
+    scheduler: Scheduler,
+}
+
+impl Playback {
+    /// Loads `scene` fresh, ready for its first tick.
+    pub fn new(scene: &str) -> Result<Self, Error> {
+        let (ecs, grid) = scene_system::load_scene_headless(scene)?;
+
+        Ok(Self {
+            scene: scene.to_string(),
+            ecs,
+            grid,
+            level_state: LevelState::default(),
+            move_history: MoveHistory::new(),
+            scheduler: Scheduler::new(SYSTEMS)?,
+        })
+    }
+
+    /// Runs the Fixed systems for one tick.
+    pub fn run_tick(&mut self, tick: u64, actions: &ActionMap) -> Result<(), Error> {
+        self.scheduler.run(
+            Tick::Fixed,
+            SceneMode::Playing,
+            &mut SystemParameters {
+                ecs: &mut self.ecs,
+                grid: &mut self.grid,
+                move_history: &mut self.move_history,
+                level_state: &mut self.level_state,
+                actions,
+                delta_time: TimeKeeper::SIXTIETH,
+                tick_count: tick,
+                runtime: None,
+                tick_state: Default::default(),
+                commands: CommandBuffer::new(),
+            },
+        )
+    }
+
+    /// Finishes off a frame, following a Scene change the same way `Clockwork` does.
+    pub fn end_frame(&mut self) -> Result<(), Error> {
+        scene_graph::walk_graph(
+            &mut self.ecs.component_database.transforms,
+            &self.ecs.component_database.graph_nodes,
+        );
+
+        let next_scene = scene_system::NEXT_SCENE.lock().unwrap().take();
+        if let Some(next_scene) = next_scene {
+            *self = Playback::new(next_scene.name())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Plays a Replay through the headless Fixed systems. Scene changes are
+/// followed once the frame is over, the same way `Clockwork` follows them.
+pub fn play_replay(replay: &Replay) -> Result<Playback, Error> {
+    let mut playback = Playback::new(&replay.scene)?;
+
+    let mut frames = replay.frames.iter().peekable();
+    while let Some(frame) = frames.next() {
+        playback.run_tick(frame.tick, &frame.actions)?;
+
+        // The rest of this frame runs before we render...
+        if frames
+            .peek()
+            .map_or(false, |next_frame| next_frame.tick == frame.tick)
+        {
+            continue;
+        }
+
+        playback.end_frame()?;
+    }
+
+    Ok(playback)
+}
+
+/// Plays a Replay back and checks that it ends where it did when it was
+/// recorded. Returns false if it didn't.
+pub fn verify_replay(replay: &Replay) -> Result<bool, Error> {
+    let playback = play_replay(replay)?;
+    let mut matches = true;
+
+    if let Some(final_scene) = &replay.final_scene {
+        if &playback.scene != final_scene {
+            error!(
+                "Replay ended in Scene {}, but was recorded ending in {}",
+                playback.scene, final_scene
+            );
+            matches = false;
+        }
+    }
+
+    if let Some(final_grid) = &replay.final_grid {
+        if &playback.grid.snapshot() != final_grid {
+            error!("Replay ended with a different Grid than it was recorded with!");
+            matches = false;
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Handles `--replay <path>`, which plays a Replay back without opening a window.
+/// Returns None if it wasn't asked for, or whether the Replay matched its recording.
+pub fn run_from_args(args: &[String]) -> Option<Result<bool, Error>> {
+    let i = args.iter().position(|arg| arg == "--replay")?;
+    let path = match args.get(i + 1) {
+        Some(path) => path,
+        None => return Some(Err(anyhow!("--replay needs a path!"))),
+    };
+
+    let result = serialization_util::replays::load_replay(path).and_then(|replay| {
+        let matches = verify_replay(&replay)?;
+        if matches {
+            println!("{}: played {} frames and matched", path, replay.frames.len());
+        } else {
+            println!("{}: DID NOT MATCH its recording", path);
+        }
+
+        Ok(matches)
+    });
+
+    Some(result)
+}
+
+/// Finds the path after `--record`, if there is one.
+pub fn record_path_from_args(args: &[String]) -> Option<String> {
+    let i = args.iter().position(|arg| arg == "--record")?;
+    args.get(i + 1).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Input, InputBindings, MovementRepeat};
+    use winit::event::VirtualKeyCode as VK;
+
+    // Plays Scene 1 live, pressing each key on its tick, and records it the way `Clockwork` does
+    fn record_scene_one(key_presses: &[(u64, VK)]) -> Replay {
+        let mut playback = Playback::new("1").unwrap();
+        let mut recorder = ReplayRecorder::new("never_saved.yaml".to_string(), "1".to_string());
+
+        let mut input = Input::new();
+        let bindings = InputBindings::default();
+        let mut action_map = ActionMap::default();
+        let mut movement_repeat = MovementRepeat::default();
+
+        let last_tick = key_presses.last().map_or(0, |(tick, _)| *tick);
+        for tick in 0..=last_tick + 10 {
+            // Each key is tapped, so it's let go again on the next tick
+            input.clear_input();
+            input.kb_input.held_keys.clear();
+            for (_, key) in key_presses.iter().filter(|(press_tick, _)| *press_tick == tick) {
+                input.kb_input.pressed_keys.push(*key);
+                input.kb_input.held_keys.push(*key);
+            }
+
+            action_map.update(&input, &bindings, &mut movement_repeat, TimeKeeper::SIXTIETH);
+            recorder.record(tick, &action_map);
+            playback.run_tick(tick, &action_map).unwrap();
+            playback.end_frame().unwrap();
+        }
+
+        recorder.into_replay(playback.scene.clone(), &playback.grid)
+    }
+
+    #[test]
+    fn a_recorded_replay_plays_back_the_same() {
+        let key_presses = [
+            (12, VK::Right),
+            (30, VK::Up),
+            (31, VK::Up),
+            (55, VK::Z),
+            (70, VK::E),
+            (80, VK::Left),
+        ];
+        let replay = record_scene_one(&key_presses);
+        assert_eq!(replay.scene, "1");
+        assert_eq!(replay.frames.len(), key_presses.len());
+        assert!(replay
+            .final_grid
+            .as_ref()
+            .unwrap()
+            .iter()
+            .any(|cell| cell.is_empty() == false));
+        assert!(verify_replay(&replay).unwrap());
+
+        // ...and the same frames don't match a recording which ended on another board
+        let mut tampered = replay.clone();
+        for cell in tampered.final_grid.as_mut().unwrap().iter_mut() {
+            cell.clear();
+        }
+        assert!(verify_replay(&tampered).unwrap() == false);
+    }
+}
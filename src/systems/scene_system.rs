@@ -1,5 +1,5 @@
 use super::{
-    serialization_util, Scene, SceneMode, SerializedEntity, SingletonDatabase, ENTITY_SUBPATH,
+    grid_system::Grid, scene_graph, serialization_util, Ecs, Scene, SceneMode, SingletonDatabase,
     PREFAB_DIRECTORY, SCENE_DIRECTORY, SINGLETONS_SUBPATH,
 };
 use anyhow::Error;
@@ -30,6 +30,35 @@ pub fn set_next_scene(scene: Scene) -> bool {
     true
 }
 
+/// Loads a Scene without a window or any runtime resources, for tools which only
+/// care about the board. This sets the current Scene and clears the next one.
+pub fn load_scene_headless(scene_name: &str) -> Result<(Ecs, Grid), Error> {
+    *CURRENT_SCENE.lock().unwrap() = Scene::new(scene_name.to_string());
+    *NEXT_SCENE.lock().unwrap() = None;
+    scene_graph::clear_root();
+
+    let prefabs = serialization_util::prefabs::load_all_prefabs()?;
+    let mut ecs = Ecs::new(&prefabs)?;
+    if let Err(e) = ecs.singleton_database.level.inner().validate() {
+        error!("{}'s Level Goals are invalid: {}", scene_name, e);
+    }
+
+    let mut grid = Grid::new(
+        ecs.singleton_database.grid.inner().clone(),
+        serialization_util::interaction_rules::load_interaction_rules()?,
+    );
+
+    ecs.initialize_board(&mut grid);
+
+    // We register on the Grid before walking, in the same order `Clockwork` does
+    scene_graph::walk_graph(
+        &mut ecs.component_database.transforms,
+        &ecs.component_database.graph_nodes,
+    );
+
+    Ok((ecs, grid))
+}
+
 pub fn create_scene(scene_name: &str) -> Result<bool, Error> {
     let scene = Scene::new(scene_name.to_string());
 
@@ -41,18 +70,14 @@ pub fn create_scene(scene_name: &str) -> Result<bool, Error> {
     let scene_path = format!("{}/{}", SCENE_DIRECTORY, scene_name);
     std::fs::create_dir_all(&scene_path)?;
 
-    // Entities Data
-    {
-        let blank_entity_save_data: Vec<SerializedEntity> = vec![];
-        let entity_path = format!("{}/{}", scene_path, ENTITY_SUBPATH);
-        serialization_util::save_serialized_file(&blank_entity_save_data, &entity_path)?;
-    }
+    // Entities Data, which is a file per entity
+    std::fs::create_dir_all(scene.entity_directory())?;
 
     // Make a blank singleton database!
     {
         let singleton_database_blank: SingletonDatabase = SingletonDatabase::default();
         let singleton_path = format!("{}/{}", scene_path, SINGLETONS_SUBPATH);
-        serialization_util::save_serialized_file(&singleton_database_blank, &singleton_path)?;
+        serialization_util::save_versioned_file(&singleton_database_blank, &singleton_path)?;
     }
 
     Ok(true)
@@ -0,0 +1,429 @@
+use super::{
+    cardinals::{CardinalPrime, FacingHorizontal},
+    grid_system::{self, BoardEffect, Grid, GridCell},
+    level_system, scene_system, Entity, GridType, LevelGoals, ENTITY_DIRECTORY_SUBPATH, ENTITY_SUBPATH,
+    SCENE_DIRECTORY,
+};
+use anyhow::Error;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+pub const DEFAULT_MAX_STATES: usize = 1_000_000;
+
+/// One thing a player can do. These map onto `ActionMap::move_direction`
+/// and `ActionMap::switch_active_player`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SolverAction {
+    Move(CardinalPrime),
+    Switch(FacingHorizontal),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolverResult {
+    /// `actions` takes the fewest Turns possible. Switching players isn't a Turn.
+    Solved {
+        actions: Vec<SolverAction>,
+        turns: usize,
+        states_explored: usize,
+    },
+    Unsolvable {
+        states_explored: usize,
+    },
+    LimitReached {
+        states_explored: usize,
+    },
+}
+
+/// Everything the solver needs to know about a Scene, pulled out of its Ecs.
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub grid: Grid,
+    pub grid_types: HashMap<Entity, GridType>,
+    /// In the order `player_system` switches between them.
+    pub players: Vec<Entity>,
+    pub scene_switchers: HashSet<Entity>,
+    pub goals: LevelGoals,
+}
+
+/// Loads a Scene from disk without a window and pulls its Puzzle out.
+/// This sets the current Scene.
+pub fn load_puzzle(scene_name: &str) -> Result<Puzzle, Error> {
+    let (ecs, grid) = scene_system::load_scene_headless(scene_name)?;
+
+    Ok(Puzzle {
+        grid,
+        grid_types: ecs
+            .component_database
+            .grid_objects
+            .iter()
+            .map(|grid_object| (grid_object.entity_id(), grid_object.inner().grid_type()))
+            .collect(),
+        players: ecs
+            .component_database
+            .players
+            .iter()
+            .map(|player| player.entity_id())
+            .collect(),
+        scene_switchers: ecs
+            .component_database
+            .scene_switchers
+            .iter()
+            .map(|scene_switcher| scene_switcher.entity_id())
+            .collect(),
+        goals: ecs.singleton_database.level.inner().clone(),
+    })
+}
+
+/// Searches for the solution with the fewest Turns. A Puzzle is solved once its
+/// LevelGoals are met, or once something reaches a SceneSwitcher.
+pub fn solve(puzzle: &Puzzle, max_states: usize) -> SolverResult {
+    let grid_type_of = |entity: &Entity| puzzle.grid_types.get(entity).copied();
+
+    // Switching is free and moving costs a Turn, so switches go on the front
+    // of the queue and moves on the back.
+    let mut queue = VecDeque::new();
+    queue.push_back((puzzle.grid.clone(), 0, Vec::new()));
+
+    let mut visited: HashSet<(Vec<GridCell>, usize)> = HashSet::new();
+
+    while let Some((grid, active_player, actions)) = queue.pop_front() {
+        if visited.insert((grid.snapshot(), active_player)) == false {
+            continue;
+        }
+
+        if visited.len() > max_states {
+            return SolverResult::LimitReached {
+                states_explored: visited.len(),
+            };
+        }
+
+        let turns = count_turns(&actions);
+
+        // Switching Players
+        if puzzle.players.len() > 1 {
+            for &direction in &[FacingHorizontal::Right, FacingHorizontal::Left] {
+                let next_player = switch_player(active_player, direction, puzzle.players.len());
+
+                let mut next_actions = actions.clone();
+                next_actions.push(SolverAction::Switch(direction));
+                queue.push_front((grid.clone(), next_player, next_actions));
+            }
+        }
+
+        // Moving
+        if puzzle
+            .goals
+            .turn_limit
+            .map_or(false, |turn_limit| turns >= turn_limit)
+        {
+            continue;
+        }
+
+        let player = match puzzle.players.get(active_player) {
+            Some(player) => *player,
+            None => continue,
+        };
+        let current_position = match grid.find(player) {
+            Some(current_position) => current_position,
+            None => continue,
+        };
+
+        for &movement in &[
+            CardinalPrime::Right,
+            CardinalPrime::Up,
+            CardinalPrime::Left,
+            CardinalPrime::Down,
+        ] {
+            let next_position = match grid.info().move_position(current_position, movement) {
+                Some(next_position) => next_position,
+                None => continue,
+            };
+
+            let mut next_grid = grid.clone();
+            next_grid.clear_events();
+
+            let mut effects = vec![];
+            if grid_system::attempt_to_move(
+                &player,
+                GridType::Player,
+                current_position,
+                next_position,
+                movement,
+                &mut next_grid,
+                &grid_type_of,
+                &mut effects,
+            ) == false
+            {
+                continue;
+            }
+
+            let mut next_actions = actions.clone();
+            next_actions.push(SolverAction::Move(movement));
+
+            let reached_scene_switcher = effects.iter().any(|effect| match effect {
+                BoardEffect::SwitchScene { occupant, .. } => puzzle.scene_switchers.contains(occupant),
+                _ => false,
+            });
+
+            if reached_scene_switcher || level_system::goals_met(&puzzle.goals, &next_grid, &grid_type_of) {
+                return SolverResult::Solved {
+                    actions: next_actions,
+                    turns: turns + 1,
+                    states_explored: visited.len(),
+                };
+            }
+
+            queue.push_back((next_grid, active_player, next_actions));
+        }
+    }
+
+    SolverResult::Unsolvable {
+        states_explored: visited.len(),
+    }
+}
+
+/// Every Scene on disk with entity data, sorted by name.
+pub fn all_scene_names() -> Result<Vec<String>, Error> {
+    let mut scene_names = vec![];
+
+    for entry in std::fs::read_dir(SCENE_DIRECTORY)? {
+        let path = entry?.path();
+        // A Scene has its entities in one file, or in a directory with a file each
+        if path.join(ENTITY_SUBPATH).exists() || path.join(ENTITY_DIRECTORY_SUBPATH).is_dir() {
+            if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                scene_names.push(name.to_string());
+            }
+        }
+    }
+    scene_names.sort();
+
+    Ok(scene_names)
+}
+
+/// Handles `--solve <scene>` and `--validate`, which check Scenes without opening
+/// a window. Returns None if neither was asked for, or whether every Scene was solvable.
+pub fn run_from_args(args: &[String]) -> Option<Result<bool, Error>> {
+    let max_states = match args.iter().position(|arg| arg == "--max-states") {
+        Some(i) => match args.get(i + 1).map(|arg| arg.parse::<usize>()) {
+            Some(Ok(max_states)) => max_states,
+            _ => return Some(Err(anyhow!("--max-states needs a number!"))),
+        },
+        None => DEFAULT_MAX_STATES,
+    };
+
+    let scene_names = if let Some(i) = args.iter().position(|arg| arg == "--solve") {
+        match args.get(i + 1) {
+            Some(scene_name) => vec![scene_name.clone()],
+            None => return Some(Err(anyhow!("--solve needs a Scene name!"))),
+        }
+    } else if args.iter().any(|arg| arg == "--validate") {
+        match all_scene_names() {
+            Ok(scene_names) => scene_names,
+            Err(e) => return Some(Err(e)),
+        }
+    } else {
+        return None;
+    };
+
+    let mut all_solved = true;
+    for scene_name in scene_names {
+        let puzzle = match load_puzzle(&scene_name) {
+            Ok(puzzle) => puzzle,
+            Err(e) => return Some(Err(e.context(format!("Couldn't load Scene {}", scene_name)))),
+        };
+
+        if let Err(e) = puzzle.goals.validate() {
+            all_solved = false;
+            println!("{}: INVALID GOALS: {}", scene_name, e);
+            continue;
+        }
+
+        match solve(&puzzle, max_states) {
+            SolverResult::Solved {
+                actions,
+                turns,
+                states_explored,
+            } => {
+                println!(
+                    "{}: solved in {} turns ({} states explored)",
+                    scene_name, turns, states_explored
+                );
+                println!("    {:?}", actions);
+            }
+            SolverResult::Unsolvable { states_explored } => {
+                all_solved = false;
+                println!("{}: UNSOLVABLE ({} states explored)", scene_name, states_explored);
+            }
+            SolverResult::LimitReached { states_explored } => {
+                all_solved = false;
+                println!(
+                    "{}: gave up after {} states. Try a higher --max-states",
+                    scene_name, states_explored
+                );
+            }
+        }
+    }
+
+    Some(Ok(all_solved))
+}
+
+fn count_turns(actions: &[SolverAction]) -> usize {
+    actions
+        .iter()
+        .filter(|action| match action {
+            SolverAction::Move(_) => true,
+            SolverAction::Switch(_) => false,
+        })
+        .count()
+}
+
+/// Matches the wrap-around in `player_system::player_update`.
+fn switch_player(active_player: usize, direction: FacingHorizontal, player_count: usize) -> usize {
+    match direction {
+        FacingHorizontal::Right => (active_player + 1) % player_count,
+        FacingHorizontal::Left => (active_player + player_count - 1) % player_count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityAllocator, GoalCondition, GridInfo, InteractionRules};
+
+    struct PuzzleBuilder {
+        puzzle: Puzzle,
+        entity_allocator: EntityAllocator,
+    }
+
+    impl PuzzleBuilder {
+        fn new(width: usize, height: usize) -> Self {
+            let grid_info = GridInfo {
+                width,
+                height,
+                ..Default::default()
+            };
+
+            Self {
+                puzzle: Puzzle {
+                    grid: Grid::new(grid_info, InteractionRules::default()),
+                    grid_types: HashMap::new(),
+                    players: vec![],
+                    scene_switchers: HashSet::new(),
+                    goals: LevelGoals::default(),
+                },
+                entity_allocator: EntityAllocator::new(),
+            }
+        }
+
+        fn spawn(&mut self, grid_type: GridType, position: (usize, usize)) -> Entity {
+            let entity = self.entity_allocator.allocate();
+            self.puzzle.grid.insert(position, entity);
+            self.puzzle.grid_types.insert(entity, grid_type);
+            if grid_type == GridType::Player {
+                self.puzzle.players.push(entity);
+            }
+
+            entity
+        }
+
+        fn scene_switcher(&mut self, position: (usize, usize)) {
+            let flag = self.spawn(GridType::Flag, position);
+            self.puzzle.scene_switchers.insert(flag);
+        }
+    }
+
+    // The Player has to push the Pushable two cells right, onto the target
+    fn two_push_puzzle() -> Puzzle {
+        let mut builder = PuzzleBuilder::new(5, 3);
+        builder.spawn(GridType::Player, (0, 1));
+        builder.spawn(GridType::Pushable, (1, 1));
+        builder.spawn(GridType::Custom(0), (3, 1));
+        builder.puzzle.goals.conditions.push(GoalCondition::AllOn {
+            mover: GridType::Pushable,
+            target: GridType::Custom(0),
+        });
+
+        builder.puzzle
+    }
+
+    fn solution(result: SolverResult) -> (Vec<SolverAction>, usize) {
+        match result {
+            SolverResult::Solved { actions, turns, .. } => (actions, turns),
+            _ => panic!("Expected a solution, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn solvable_puzzles_are_solved_in_the_fewest_turns() {
+        let (actions, turns) = solution(solve(&two_push_puzzle(), DEFAULT_MAX_STATES));
+
+        assert_eq!(turns, 2);
+        assert_eq!(
+            actions,
+            vec![
+                SolverAction::Move(CardinalPrime::Right),
+                SolverAction::Move(CardinalPrime::Right)
+            ]
+        );
+    }
+
+    #[test]
+    fn a_pushable_stuck_in_a_corner_is_unsolvable() {
+        let mut builder = PuzzleBuilder::new(3, 3);
+        builder.spawn(GridType::Player, (2, 0));
+        builder.spawn(GridType::Pushable, (0, 0));
+        builder.spawn(GridType::Custom(0), (2, 2));
+        builder.puzzle.goals.conditions.push(GoalCondition::AllOn {
+            mover: GridType::Pushable,
+            target: GridType::Custom(0),
+        });
+
+        match solve(&builder.puzzle, DEFAULT_MAX_STATES) {
+            SolverResult::Unsolvable { .. } => {}
+            result => panic!("Expected no solution, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn the_turn_limit_cuts_the_search_off() {
+        let mut puzzle = two_push_puzzle();
+
+        puzzle.goals.turn_limit = Some(1);
+        match solve(&puzzle, DEFAULT_MAX_STATES) {
+            SolverResult::Unsolvable { .. } => {}
+            result => panic!("Expected no solution in 1 Turn, but got {:?}", result),
+        }
+
+        puzzle.goals.turn_limit = Some(2);
+        assert_eq!(solution(solve(&puzzle, DEFAULT_MAX_STATES)).1, 2);
+    }
+
+    #[test]
+    fn the_search_gives_up_after_max_states() {
+        match solve(&two_push_puzzle(), 1) {
+            SolverResult::LimitReached { states_explored } => assert_eq!(states_explored, 2),
+            result => panic!("Expected to give up, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn switching_players_does_not_take_a_turn() {
+        // The first Player is three Turns from the SceneSwitcher, but the second
+        // is only one, so the best solution switches over for free
+        let mut builder = PuzzleBuilder::new(5, 3);
+        builder.spawn(GridType::Player, (1, 1));
+        builder.spawn(GridType::Player, (4, 0));
+        builder.scene_switcher((4, 1));
+        builder.puzzle.goals.turn_limit = Some(1);
+
+        let (actions, turns) = solution(solve(&builder.puzzle, DEFAULT_MAX_STATES));
+
+        assert_eq!(turns, 1);
+        assert_eq!(
+            actions,
+            vec![
+                SolverAction::Switch(FacingHorizontal::Left),
+                SolverAction::Move(CardinalPrime::Up)
+            ]
+        );
+    }
+}
@@ -1,44 +1,87 @@
-// use super::{Component, ResourcesDatabase, SoundPlayer, SoundResource, SoundSource};
-// use failure::Error;
-// use rodio::{Decoder, Sink};
-// use std::io::Cursor;
+use super::{
+    game_config::VolumeBuses, ComponentList, Join, Maybe, ResourcesDatabase, SceneMusic, SoundName,
+    SoundPlayer, SoundSource, Transform, Vec2,
+};
+use anyhow::Error;
+use rodio::{source::ChannelVolume, Decoder};
+use std::io::Cursor;
+use std::sync::Arc;
 
-// pub fn play_sounds<'a>(
-//     sound_sources: impl Iterator<Item = &'a mut Component<SoundSource>>,
-//     sound_player: &mut SoundPlayer,
-//     resources: &ResourcesDatabase,
-// ) -> Result<(), Error> {
-//     for sound_source_component in sound_sources {
-//         let sound_source = sound_source_component.inner_mut();
+/// Plays every queued sound. Queued sounds on a muted SoundSource are thrown
+/// away rather than held onto, so unmuting doesn't play a backlog. Sounds which
+/// aren't loaded are logged and skipped. Spatial sounds are heard from `listener_position`.
+pub fn play_sounds(
+    sound_sources: &mut ComponentList<SoundSource>,
+    transforms: &ComponentList<Transform>,
+    listener_position: Vec2,
+    sound_player: &mut SoundPlayer,
+    resources: &ResourcesDatabase,
+) {
+    for (_, (sound_source, transform)) in (sound_sources, Maybe(transforms)).join() {
+        if let Some(sound) = sound_source.sound_to_play.take() {
+            if sound_source.muted == false {
+                match get_sound(&sound, resources) {
+                    Ok(sound) => match transform.map(|transform| transform.world_position()) {
+                        Some(position) if sound_source.spatial => {
+                            let channel_volumes =
+                                sound_source.falloff.channel_volumes(position - listener_position);
+                            sound_player.play(ChannelVolume::new(sound, channel_volumes.to_vec()));
+                        }
+                        _ => {
+                            sound_player.play(sound);
+                        }
+                    },
+                    Err(e) => error!("{}", e),
+                }
+            }
+        }
+    }
+}
 
-//         if sound_source.muted == false {
-//             if let Some(sound) = sound_source.sound_to_play {
-//                 let sink: &Sink = match sound_player.sinks.iter().find(|&x| x.empty()) {
-//                     Some(this_sink) => this_sink,
-//                     None => {
-//                         sound_player.sinks.push(Sink::new(&sound_player.device));
-//                         info!(
-//                             "Sound sink expanded -- we now have {} sinks.",
-//                             sound_player.sinks.len()
-//                         );
-//                         &sound_player.sinks.last().unwrap()
-//                     }
-//                 };
+/// Crossfades to the Scene's track if it isn't already playing. Since this follows
+/// whatever Scene is loaded, changing Scenes crossfades between their tracks. A track
+/// which can't be played isn't tried again until its file changes.
+pub fn update_music(
+    scene_music: &SceneMusic,
+    sound_player: &mut SoundPlayer,
+    resources: &ResourcesDatabase,
+    volumes: &VolumeBuses,
+    delta_time: f32,
+) -> Result<(), Error> {
+    if sound_player.current_music() != scene_music.track.as_ref() {
+        match &scene_music.track {
+            Some(track) if sound_player.music_failed(track) => {}
+            Some(track) => match get_sound(track, resources) {
+                Ok(sound) => {
+                    sound_player.play_music(track.clone(), sound, scene_music.volume, scene_music.fade_time)
+                }
+                Err(e) => {
+                    error!("Couldn't play {} as music: {}", track, e);
+                    sound_player.set_music_failed(track.clone());
+                    sound_player.stop_music(scene_music.fade_time);
+                }
+            },
+            None => sound_player.stop_music(scene_music.fade_time),
+        }
+    }
 
-//                 sink.append(get_sound(&sound, resources)?);
-//                 sound_source.sound_to_play = None;
-//             }
-//         }
-//     }
+    sound_player.set_music_volume(scene_music.volume);
+    sound_player.update(delta_time, volumes);
 
-//     Ok(())
-// }
+    Ok(())
+}
 
-// fn get_sound(
-//     sound_resource: &SoundResource,
-//     resources: &ResourcesDatabase,
-// ) -> Result<Decoder<Cursor<&'static [u8]>>, Error> {
-//     Ok(Decoder::new(
-//         resources.sounds.get(&sound_resource).unwrap().clone(),
-//     )?)
-// }
+fn get_sound(
+    sound_name: &SoundName,
+    resources: &ResourcesDatabase,
+) -> Result<Decoder<Cursor<Arc<[u8]>>>, Error> {
+    let sound_file = resources.sounds.get(sound_name).ok_or_else(|| {
+        anyhow!(
+            "There's no sound named {} in {}!",
+            sound_name,
+            super::SOUND_DIRECTORY
+        )
+    })?;
+
+    Ok(Decoder::new(Cursor::new(sound_file.data.clone()))?)
+}